@@ -0,0 +1,73 @@
+use alloy::sol;
+
+// Collateral token (USDC).
+sol! {
+    #[sol(rpc)]
+    interface IERC20 {
+        function allowance(address owner, address spender) external view returns (uint256);
+        function approve(address spender, uint256 amount) external returns (bool);
+        function balanceOf(address account) external view returns (uint256);
+    }
+}
+
+// Conditional tokens outcome positions.
+sol! {
+    #[sol(rpc)]
+    interface IERC1155 {
+        function isApprovedForAll(address owner, address operator) external view returns (bool);
+        function setApprovalForAll(address operator, bool approved) external;
+        function balanceOf(address account, uint256 id) external view returns (uint256);
+    }
+}
+
+// Gnosis conditional tokens framework, holds all outcome positions.
+sol! {
+    #[sol(rpc)]
+    interface IConditionalTokens {
+        function splitPosition(address collateralToken, bytes32 parentCollectionId, bytes32 conditionId, uint256[] partition, uint256 amount) external;
+        function mergePositions(address collateralToken, bytes32 parentCollectionId, bytes32 conditionId, uint256[] partition, uint256 amount) external;
+        function redeemPositions(address collateralToken, bytes32 parentCollectionId, bytes32 conditionId, uint256[] indexSets) external;
+    }
+}
+
+// Polymarket neg risk adapter, wraps collateral for neg risk markets.
+sol! {
+    #[sol(rpc)]
+    interface INegRiskAdapter {
+        function splitPosition(bytes32 conditionId, uint256 amount) external;
+        function mergePositions(bytes32 conditionId, uint256 amount) external;
+        function redeemPositions(bytes32 conditionId, uint256[] amounts) external;
+    }
+}
+
+// Polymarket CTF exchange, the regular and neg risk exchanges share this interface.
+sol! {
+    #[sol(rpc)]
+    interface ICTFExchange {
+        struct Order {
+            uint256 salt;
+            address maker;
+            address signer;
+            address taker;
+            uint256 tokenId;
+            uint256 makerAmount;
+            uint256 takerAmount;
+            uint256 expiration;
+            uint256 nonce;
+            uint256 feeRateBps;
+            uint8   side;
+            uint8   signatureType;
+            bytes   signature;
+        }
+
+        function cancelOrder(Order order) external;
+        function cancelOrders(Order[] orders) external;
+        function incrementNonce() external;
+        function nonces(address user) external view returns (uint256);
+
+        event OrderFilled(bytes32 indexed orderHash, address indexed maker, address indexed taker, uint256 makerAssetId, uint256 takerAssetId, uint256 makerAmountFilled, uint256 takerAmountFilled, uint256 fee);
+        event OrdersMatched(bytes32 indexed takerOrderHash, address indexed takerOrderMaker, uint256 makerAssetId, uint256 takerAssetId, uint256 makerAmountFilled, uint256 takerAmountFilled);
+        event OrderCancelled(bytes32 indexed orderHash);
+        event TokenRegistered(uint256 indexed token0, uint256 indexed token1, bytes32 indexed conditionId);
+    }
+}
@@ -0,0 +1,343 @@
+use std::{collections::{BTreeMap, BTreeSet, HashMap}, sync::Arc};
+use serde::Serialize;
+use tokio::{sync::Semaphore, task::JoinSet};
+use crate::{
+    catalog::{MarketCatalog, MarketFilter},
+    client::ClobClient,
+    fees,
+    schema::{ConditionId, Market, Orderbook, TokenId},
+};
+
+// Complete sets pay out $1: the two tokens of a binary market, or the YES tokens of every
+// market of a neg risk event, as exactly one outcome of the event resolves YES.
+// Buying a set below $1, or selling one above $1 after splitting collateral into it, is an arbitrage.
+
+// Book requests in flight at once, unless set with `with_max_concurrency`.
+pub const DEFAULT_MAX_CONCURRENCY: usize = 8;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum Direction {
+    // Buy every leg at the asks and merge or redeem the set.
+    BuyAll,
+    // Split collateral into sets and sell every leg at the bids.
+    SellAll,
+}
+
+// A token of a set and its book side, levels as (price, size) best first.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Leg {
+    pub token_id:     TokenId,
+    pub fee_rate_bps: u32,
+    pub levels:       Vec<(f64, f64)>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Opportunity {
+    pub direction: Direction,
+    // Several for a neg risk event.
+    pub markets:   Vec<ConditionId>,
+    pub token_ids: Vec<TokenId>,
+    // Worst price of each leg to execute all sets, i.e. the limit prices of the orders.
+    pub prices:    Vec<f64>,
+    // Complete sets executable at a positive edge.
+    pub sets:      f64,
+    // USDC paid for buying, or received for selling, all legs net of fees.
+    pub notional:  f64,
+    // Profit in USDC net of fees.
+    pub edge:      f64,
+}
+
+// Walks the legs' levels while a set pays out more than it costs net of taker fees.
+// Buy fees are taken in tokens, so more than a set's worth of each leg is bought to net one.
+pub fn walk(direction: Direction, markets: Vec<ConditionId>, legs: &[Leg]) -> Option<Opportunity> {
+    if legs.is_empty() {
+        return None;
+    }
+    let mut level = vec![0; legs.len()];
+    let mut left: Vec<f64> = legs.iter().map(|leg| leg.levels.first().map_or(0.0, |&(_, size)| size)).collect();
+    let mut prices = vec![0.0; legs.len()];
+    let (mut sets, mut notional) = (0.0, 0.0);
+
+    'walk: loop {
+        // Tokens of each leg per set and the set's cost or proceeds at the current levels.
+        let mut per_set_tokens = Vec::with_capacity(legs.len());
+        let mut per_set = 0.0;
+        let mut max_sets = f64::INFINITY;
+        for (i, leg) in legs.iter().enumerate() {
+            let Some(&(price, _)) = leg.levels.get(level[i]) else {
+                break 'walk;
+            };
+            let tokens = match direction {
+                Direction::BuyAll => {
+                    let tokens = 1.0 / (1.0 - fees::buy_fee(leg.fee_rate_bps, price, 1.0));
+                    per_set += price * tokens;
+                    tokens
+                }
+                Direction::SellAll => {
+                    per_set += price - fees::sell_fee(leg.fee_rate_bps, price, 1.0);
+                    1.0
+                }
+            };
+            per_set_tokens.push(tokens);
+            max_sets = max_sets.min(left[i] / tokens);
+        }
+
+        let profitable = match direction {
+            Direction::BuyAll  => per_set < 1.0,
+            Direction::SellAll => per_set > 1.0,
+        };
+        if !profitable {
+            break;
+        }
+        sets += max_sets;
+        notional += max_sets * per_set;
+        for (i, leg) in legs.iter().enumerate() {
+            prices[i] = leg.levels[level[i]].0;
+            left[i] -= max_sets * per_set_tokens[i];
+            // Against float error when the level was the smallest.
+            if left[i] <= 1e-9 {
+                level[i] += 1;
+                left[i] = leg.levels.get(level[i]).map_or(0.0, |&(_, size)| size);
+            }
+        }
+    }
+
+    if sets <= 0.0 {
+        return None;
+    }
+    let edge = match direction {
+        Direction::BuyAll  => sets - notional,
+        Direction::SellAll => notional - sets,
+    };
+    Some(Opportunity {
+        direction,
+        markets,
+        token_ids: legs.iter().map(|leg| leg.token_id).collect(),
+        prices,
+        sets,
+        notional,
+        edge,
+    })
+}
+
+// Markets of a complete set and its tokens with their fee rates.
+type CompleteSet = (Vec<ConditionId>, Vec<(TokenId, u32)>);
+
+// Complete sets of the catalog's markets matching the filter: each open binary market, and each neg risk
+// event of a matching market. Events are built from every market of the catalog sharing the neg risk
+// market id, and skipped if any of them is closed, not tradable, e.g. a placeholder of an augmented event
+// still to be named, or lacks a YES token, as the remaining YES tokens wouldn't pay out $1 in every outcome.
+fn complete_sets(catalog: &MarketCatalog, filter: &MarketFilter) -> Vec<CompleteSet> {
+    let mut sets = Vec::new();
+    let mut event_ids = BTreeSet::new();
+    for market in catalog.filter(filter).into_iter().filter(|market| tradable(market)) {
+        if let [a, b] = market.tokens.as_slice() {
+            let fee = market.taker_base_fee;
+            sets.push((vec![market.condition_id], vec![(a.token_id, fee), (b.token_id, fee)]));
+        }
+        if market.neg_risk && !market.neg_risk_market_id.is_empty() {
+            event_ids.insert(market.neg_risk_market_id.as_str());
+        }
+    }
+
+    let mut events: BTreeMap<&str, Vec<&Market>> = BTreeMap::new();
+    for market in catalog.iter().filter(|market| market.neg_risk && event_ids.contains(market.neg_risk_market_id.as_str())) {
+        events.entry(&market.neg_risk_market_id).or_default().push(market);
+    }
+    for event in events.values().filter(|event| event.len() > 1 && event.iter().all(|market| tradable(market))) {
+        let legs = event
+            .iter()
+            .map(|market| market.outcome("Yes").map(|token| (token.token_id, market.taker_base_fee)))
+            .collect::<Option<Vec<_>>>();
+        if let Some(legs) = legs {
+            sets.push((event.iter().map(|market| market.condition_id).collect(), legs));
+        }
+    }
+    sets
+}
+
+// Placeholder markets don't take orders until named.
+fn tradable(market: &Market) -> bool {
+    market.active && !market.closed && market.accepting_orders && market.enable_order_book && !market.question.is_empty()
+}
+
+// Scans markets for complete sets mispriced against their books.
+#[derive(Debug, Clone)]
+pub struct ArbitrageScanner {
+    client:          ClobClient,
+    min_edge:        f64,
+    max_concurrency: usize,
+}
+
+impl ArbitrageScanner {
+
+    pub fn new(client: ClobClient) -> Self {
+        Self { client, min_edge: 0.0, max_concurrency: DEFAULT_MAX_CONCURRENCY }
+    }
+
+    // Only report opportunities with at least this profit in USDC.
+    pub fn with_min_edge(mut self, min_edge: f64) -> Self {
+        self.min_edge = min_edge;
+        self
+    }
+
+    // At most this many book requests in flight at once, at least one.
+    pub fn with_max_concurrency(mut self, max_concurrency: usize) -> Self {
+        self.max_concurrency = max_concurrency.max(1);
+        self
+    }
+
+    // Opportunities in the catalog's markets matching the filter, best edge first, see `complete_sets`.
+    // The catalog should hold every market so that neg risk events are complete, e.g. from `MarketCatalog::load`.
+    // Tokens whose book fails to fetch are logged and their sets skipped.
+    pub async fn scan(&self, catalog: &MarketCatalog, filter: &MarketFilter) -> Vec<Opportunity> {
+        let sets = complete_sets(catalog, filter);
+        let token_ids: BTreeSet<TokenId> = sets.iter().flat_map(|(_, tokens)| tokens.iter().map(|&(token_id, _)| token_id)).collect();
+        let books = self.fetch_books(token_ids).await;
+
+        let mut opportunities = Vec::new();
+        for (markets, tokens) in sets {
+            let Some(books) = tokens.iter().map(|(token_id, _)| books.get(token_id)).collect::<Option<Vec<_>>>() else {
+                continue;
+            };
+            for direction in [Direction::BuyAll, Direction::SellAll] {
+                let legs: Vec<Leg> = tokens.iter().zip(&books).map(|(&(token_id, fee_rate_bps), book)| Leg {
+                    token_id,
+                    fee_rate_bps,
+                    levels: match direction {
+                        Direction::BuyAll  => book.ask_levels(),
+                        Direction::SellAll => book.bid_levels(),
+                    },
+                }).collect();
+                opportunities.extend(walk(direction, markets.clone(), &legs).filter(|opportunity| opportunity.edge >= self.min_edge));
+            }
+        }
+        opportunities.sort_by(|a, b| b.edge.total_cmp(&a.edge));
+        opportunities
+    }
+
+    // Books of the tokens, at most `max_concurrency` fetched at once.
+    async fn fetch_books(&self, token_ids: impl IntoIterator<Item = TokenId>) -> HashMap<TokenId, Orderbook> {
+        let permits = Arc::new(Semaphore::new(self.max_concurrency));
+        let mut tasks = JoinSet::new();
+        for token_id in token_ids {
+            let client = self.client.clone();
+            let permits = permits.clone();
+            tasks.spawn(async move {
+                // Cannot fail, the semaphore is never closed.
+                let _permit = permits.acquire_owned().await.unwrap();
+                (token_id, client.get_market_book(&token_id.to_string()).await)
+            });
+        }
+
+        let mut books = HashMap::new();
+        while let Some(joined) = tasks.join_next().await {
+            match joined {
+                Ok((token_id, Ok(book))) => {
+                    books.insert(token_id, book);
+                }
+                Ok((token_id, Err(e))) => tracing::warn!("skipping token {} without book: {}", token_id, e),
+                Err(e) => tracing::warn!("book fetch task failed: {}", e),
+            }
+        }
+        books
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy::primitives::{B256, U256};
+    use crate::{fees::tests::assert_close, schema::get_market};
+    use super::*;
+
+    fn leg(token_id: u64, fee_rate_bps: u32, levels: &[(f64, f64)]) -> Leg {
+        Leg { token_id: TokenId(U256::from(token_id)), fee_rate_bps, levels: levels.to_vec() }
+    }
+
+    // Market `i` of the neg risk event, yes and no tokens 2i and 2i + 1.
+    fn event_market(event: &str, i: u8) -> Market {
+        let mut market = get_market();
+        market.condition_id = ConditionId(B256::repeat_byte(i));
+        market.tokens[0].token_id = TokenId(U256::from(2 * i));
+        market.tokens[1].token_id = TokenId(U256::from(2 * i + 1));
+        market.neg_risk_market_id = event.to_string();
+        market
+    }
+
+    fn event_sets(catalog: &MarketCatalog, filter: &MarketFilter) -> Vec<Vec<ConditionId>> {
+        complete_sets(catalog, filter).into_iter().map(|(markets, _)| markets).filter(|markets| markets.len() > 1).collect()
+    }
+
+    #[test]
+    fn test_walk_binary() {
+        let markets = vec![ConditionId(B256::repeat_byte(1))];
+        // 0.45 + 0.50 for 100 sets, then 0.45 + 0.56 isn't worth it.
+        let legs = [leg(1, 0, &[(0.45, 300.0)]), leg(2, 0, &[(0.50, 100.0), (0.56, 500.0)])];
+        let opportunity = walk(Direction::BuyAll, markets.clone(), &legs).unwrap();
+        assert_close(opportunity.sets, 100.0);
+        assert_close(opportunity.notional, 95.0);
+        assert_close(opportunity.edge, 5.0);
+        assert_eq!(opportunity.prices, vec![0.45, 0.50]);
+
+        // A 2% fee at even odds costs more than the 1% edge.
+        let legs = [leg(1, 200, &[(0.49, 100.0)]), leg(2, 200, &[(0.50, 100.0)])];
+        assert!(walk(Direction::BuyAll, markets.clone(), &legs).is_none());
+
+        // Bids summing above 1 across two levels.
+        let legs = [leg(1, 0, &[(0.55, 50.0), (0.52, 50.0), (0.40, 50.0)]), leg(2, 0, &[(0.50, 200.0)])];
+        let opportunity = walk(Direction::SellAll, markets, &legs).unwrap();
+        assert_close(opportunity.sets, 100.0);
+        assert_close(opportunity.edge, 50.0 * 0.05 + 50.0 * 0.02);
+        assert_eq!(opportunity.prices, vec![0.52, 0.50]);
+    }
+
+    #[test]
+    fn test_walk_neg_risk_event() {
+        let markets = (1..=3).map(|i| ConditionId(B256::repeat_byte(i))).collect::<Vec<_>>();
+        let legs = [leg(1, 0, &[(0.30, 10.0)]), leg(2, 0, &[(0.30, 20.0)]), leg(3, 0, &[(0.35, 30.0)])];
+        let opportunity = walk(Direction::BuyAll, markets.clone(), &legs).unwrap();
+        assert_close(opportunity.sets, 10.0);
+        assert_close(opportunity.edge, 0.5);
+        assert_eq!(opportunity.token_ids.len(), 3);
+
+        // An empty leg means no set.
+        let legs = [leg(1, 0, &[(0.30, 10.0)]), leg(2, 0, &[])];
+        assert!(walk(Direction::BuyAll, markets, &legs).is_none());
+    }
+
+    #[test]
+    fn test_complete_sets() {
+        let mut catalog = MarketCatalog::default();
+        for i in 1..=3 {
+            catalog.upsert(event_market("a", i));
+        }
+        let sets = complete_sets(&catalog, &MarketFilter::default());
+        // Each market's yes and no, and the yes tokens of the event.
+        assert_eq!(sets.len(), 4);
+        let (markets, tokens) = sets.last().unwrap();
+        assert_eq!(markets.len(), 3);
+        assert_eq!(tokens.iter().map(|(token_id, _)| *token_id).collect::<Vec<_>>(), [2u64, 4, 6].map(|id| TokenId(U256::from(id))));
+        assert!(tokens.iter().all(|&(_, fee)| fee == 200));
+
+        // Filtering down to part of an event still scans the whole event.
+        let filter = MarketFilter { tag: Some("Weather".to_string()), ..Default::default() };
+        let mut tagged = event_market("a", 1);
+        tagged.tags = Some(vec!["Weather".to_string()]);
+        catalog.upsert(tagged);
+        assert_eq!(event_sets(&catalog, &filter).len(), 1);
+        assert_eq!(event_sets(&catalog, &filter)[0].len(), 3);
+
+        // A placeholder still to be named, or a market without a yes outcome, leaves the event incomplete.
+        let mut placeholder = event_market("a", 4);
+        placeholder.accepting_orders = false;
+        catalog.upsert(placeholder);
+        assert!(event_sets(&catalog, &MarketFilter::default()).is_empty());
+        let mut unnamed = event_market("b", 5);
+        unnamed.tokens[0].outcome = "Option 1".to_string();
+        catalog.upsert(event_market("b", 6));
+        catalog.upsert(unnamed);
+        assert!(event_sets(&catalog, &MarketFilter::default()).is_empty());
+        // The binary sets of the tradable markets remain.
+        assert_eq!(complete_sets(&catalog, &MarketFilter::default()).len(), 5);
+    }
+}
@@ -1,10 +1,10 @@
-use alloy::{hex::ToHexExt, primitives::U256, signers::{k256::sha2, SignerSync}, sol, sol_types::eip712_domain};
-use alloy_signer_local::PrivateKeySigner;
+use std::str::FromStr;
+use alloy::{hex::ToHexExt, primitives::{Address, B256, U256}, signers::{k256::sha2, Signature}, sol, sol_types::{eip712_domain, Eip712Domain, SolStruct}};
 use hmac::Mac;
-use reqwest::header::HeaderMap;
-use chrono::prelude::*;
+use reqwest::header::{HeaderMap, HeaderValue};
 use base64::prelude::*;
-use crate::{client::ApiCreds, contracts::get_contracts, schema::{Order, SignedOrder}, Error};
+use zeroize::Zeroizing;
+use crate::{client::ApiCreds, contracts::{get_contracts, ContractConfig}, schema::{Order, SignedOrder}, signer::ClobSigner, Error};
 use super::Result;
 
 const ATTEST_MSG: &str = "This message attests that I control the given wallet";
@@ -20,21 +20,21 @@ sol! {
     }
 }
 
-pub fn l1_headers(signer: &PrivateKeySigner, nonce: U256) -> Result<HeaderMap> {
+// Timestamp is UTC seconds, supplied by the client's clock.
+pub async fn l1_headers(signer: &dyn ClobSigner, nonce: U256, timestamp: i64) -> Result<HeaderMap> {
     
-    let timestamp = Utc::now().timestamp();
-    let sig = sign_attest_msg(signer, timestamp, nonce)?;
+    let sig = sign_attest_msg(signer, timestamp, nonce).await?;
     
     let mut headers = HeaderMap::new();
     headers.insert("POLY_ADDRESS",   signer.address().to_string().parse()?);
-    headers.insert("POLY_SIGNATURE", sig.parse()?);
+    headers.insert("POLY_SIGNATURE", sensitive(&sig)?);
     headers.insert("POLY_TIMESTAMP", timestamp.to_string().parse()?);
     headers.insert("POLY_NONCE",     nonce.to_string().parse()?);
     
     Ok(headers)
 }
 
-fn sign_attest_msg(signer: &PrivateKeySigner, timestamp: i64, nonce: U256) -> Result<String> {
+async fn sign_attest_msg(signer: &dyn ClobSigner, timestamp: i64, nonce: U256) -> Result<String> {
 
     let domain = eip712_domain! {
         name:     "ClobAuthDomain",
@@ -49,38 +49,47 @@ fn sign_attest_msg(signer: &PrivateKeySigner, timestamp: i64, nonce: U256) -> Re
         nonce,
     };
 
+    // Signing the hash directly keeps the signer object safe.
     Ok(
         signer
-            .sign_typed_data_sync(&data, &domain)?
+            .sign_hash(&data.eip712_signing_hash(&domain))
+            .await?
             .as_bytes()
             .encode_hex_with_prefix()
     )
 }
 
 pub fn l2_headers(
-    signer: &PrivateKeySigner, 
+    signer: &dyn ClobSigner, 
     creds:  &ApiCreds,
     method: &reqwest::Method,
     path:   &str,
     body:   Option<&str>,
+    timestamp: i64,
 ) -> Result<HeaderMap> {
 
-    let timestamp = Utc::now().timestamp();
     let body = body.unwrap_or("");
     let pre_hash = format!("{timestamp}{method}{path}{body}");
     let sig = hmac_signature(&pre_hash, &creds.secret)?;
 
     let mut headers = HeaderMap::new();
     headers.insert("POLY_ADDRESS",    signer.address().to_string().parse()?);
-    headers.insert("POLY_SIGNATURE",  sig.parse()?);
+    headers.insert("POLY_SIGNATURE",  sensitive(&sig)?);
     headers.insert("POLY_TIMESTAMP",  timestamp.to_string().parse()?);
-    headers.insert("POLY_API_KEY",    creds.api_key.parse()?);
-    headers.insert("POLY_PASSPHRASE", creds.passphrase.parse()?);
+    headers.insert("POLY_API_KEY",    sensitive(&creds.api_key)?);
+    headers.insert("POLY_PASSPHRASE", sensitive(&creds.passphrase)?);
     Ok(headers)
 }
 
+// Sensitive header values are shown as "Sensitive" when the request is debug formatted.
+fn sensitive(value: &str) -> Result<HeaderValue> {
+    let mut value = HeaderValue::from_str(value)?;
+    value.set_sensitive(true);
+    Ok(value)
+}
+
 fn hmac_signature(pre_hash: &str, secret: &str) -> Result<String> {
-    let decoded_secret = BASE64_URL_SAFE.decode(secret).map_err(|_| Error::InvalidSecret)?;
+    let decoded_secret = Zeroizing::new(BASE64_URL_SAFE.decode(secret).map_err(|_| Error::InvalidSecret)?);
     // Cannot fail, SHA-256 can accept key of any length.
     let mut mac = hmac::Hmac::<sha2::Sha256>::new_from_slice(&decoded_secret).unwrap();
     mac.update(pre_hash.as_bytes());
@@ -88,23 +97,65 @@ fn hmac_signature(pre_hash: &str, secret: &str) -> Result<String> {
     Ok(BASE64_URL_SAFE.encode(result.into_bytes()))
 }
 
-pub fn sign_order(signer: &PrivateKeySigner, order: Order, neg_risk: bool) -> Result<SignedOrder> {
-
-    let chain_id = signer.chain_id().unwrap_or(137);
+// Orders are signed against the exchange they are matched on, neg risk markets have their own.
+fn order_domain(chain_id: u64, contracts: &ContractConfig, neg_risk: bool) -> Eip712Domain {
     let verifying_contract = if neg_risk {
-        get_contracts(chain_id)?.neg_risk_exchange
+        contracts.neg_risk_exchange
     } else {
-        get_contracts(chain_id)?.exchange
+        contracts.exchange
     };
-    let domain = eip712_domain! {
+    eip712_domain! {
         name:               PROTOCOL_NAME,
         version:            PROTOCOL_VERSION,
         chain_id:           chain_id,
         verifying_contract: verifying_contract,
-    };
+    }
+}
+
+// EIP-712 struct hash of the order, independent of the exchange.
+pub fn order_struct_hash(order: &Order) -> B256 {
+    order.eip712_hash_struct()
+}
+
+// EIP-712 digest of the order, this is the order id used by the CLOB.
+// Contracts are those of the chain, see `contracts::get_contracts`.
+pub fn order_hash(order: &Order, chain_id: u64, neg_risk: bool) -> Result<B256> {
+    Ok(order_hash_with_contracts(order, chain_id, &get_contracts(chain_id)?, neg_risk))
+}
+
+pub fn order_hash_with_contracts(order: &Order, chain_id: u64, contracts: &ContractConfig, neg_risk: bool) -> B256 {
+    order.eip712_signing_hash(&order_domain(chain_id, contracts, neg_risk))
+}
+
+pub fn recover_order_signer(signed_order: &SignedOrder, chain_id: u64, neg_risk: bool) -> Result<Address> {
+    let signature = Signature::from_str(&signed_order.signature).map_err(|_| Error::InvalidOrderSignature)?;
+    let hash = order_hash(&signed_order.order, chain_id, neg_risk)?;
+    signature
+        .recover_address_from_prehash(&hash)
+        .map_err(|_| Error::InvalidOrderSignature)
+}
+
+// Errors if the signature was not made by the order's `signer`.
+pub fn verify_order(signed_order: &SignedOrder, chain_id: u64, neg_risk: bool) -> Result<()> {
+    if recover_order_signer(signed_order, chain_id, neg_risk)? != signed_order.order.signer {
+        return Err(Error::InvalidOrderSignature);
+    }
+    Ok(())
+}
+
+pub async fn sign_order(
+    signer:    &dyn ClobSigner,
+    order:     Order,
+    contracts: &ContractConfig,
+    neg_risk:  bool,
+) -> Result<SignedOrder> {
+
+    let chain_id = signer.chain_id().unwrap_or(137);
+    let hash = order_hash_with_contracts(&order, chain_id, contracts, neg_risk);
 
     let signature = signer
-        .sign_typed_data_sync(&order, &domain)?
+        .sign_hash(&hash)
+        .await?
         .as_bytes()
         .encode_hex_with_prefix();
 
@@ -116,8 +167,7 @@ pub fn sign_order(signer: &PrivateKeySigner, order: Order, neg_risk: bool) -> Re
 
 #[cfg(test)]
 mod tests {
-    use std::str::FromStr;
-    use alloy::{primitives::Address, signers::Signer};
+    use alloy::signers::{local::PrivateKeySigner, Signer};
     use super::*;
 
     // Known private key for testing.
@@ -128,10 +178,10 @@ mod tests {
         PrivateKeySigner::from_str(PRIVATE_KEY).unwrap().with_chain_id(Some(CHAIN_ID))
     }
 
-    #[test]
-    fn test_sign_attest_msg() {
+    #[tokio::test]
+    async fn test_sign_attest_msg() {
         let signer = get_signer();
-        let sig = sign_attest_msg(&signer, 10000000, U256::from(23)).unwrap();
+        let sig = sign_attest_msg(&signer, 10000000, U256::from(23)).await.unwrap();
         assert_eq!(sig, "0xf62319a987514da40e57e2f4d7529f7bac38f0355bd88bb5adbb3768d80de6c1682518e0af677d5260366425f4361e7b70c25ae232aff0ab2331e2b164a1aedc1b");
     }
 
@@ -144,15 +194,12 @@ mod tests {
         let body = body.replace("'", "\""); 
         let prehash = format!("{timestamp}{method}{path}{body}");
         let secret = "AAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAA=";
-        let sig = hmac_signature(&prehash, &secret).unwrap();
+        let sig = hmac_signature(&prehash, secret).unwrap();
         assert_eq!(sig, "ZwAdJKvoYRlEKDkNMwd5BuwNNtg93kNaR_oU2HrfVvc=");
     }
 
-    #[test]
-    fn test_sign_order() {
-        let signer = get_signer();
-        let address = signer.address();
-        let order = Order {
+    fn get_order(address: Address) -> Order {
+        Order {
             salt:           U256::from(479249096354_u64),
             maker:          address,
             signer:         address,
@@ -165,8 +212,55 @@ mod tests {
             feeRateBps:     U256::from(100),
             side:           0,
             signatureType:  0,
-        };
-        let signed_order = sign_order(&signer, order, false).unwrap();
+        }
+    }
+
+    #[tokio::test]
+    async fn test_sign_order() {
+        let signer = get_signer();
+        let signed_order = sign_order(&signer, get_order(signer.address()), &get_contracts(CHAIN_ID).unwrap(), false).await.unwrap();
         assert_eq!(signed_order.signature, "0x302cd9abd0b5fcaa202a344437ec0b6660da984e24ae9ad915a592a90facf5a51bb8a873cd8d270f070217fea1986531d5eec66f1162a81f66e026db653bf7ce1c");
     }
+
+    #[tokio::test]
+    async fn test_verify_order() {
+        let signer = get_signer();
+        let mut signed_order = sign_order(&signer, get_order(signer.address()), &get_contracts(CHAIN_ID).unwrap(), false).await.unwrap();
+        assert_eq!(recover_order_signer(&signed_order, CHAIN_ID, false).unwrap(), signer.address());
+        verify_order(&signed_order, CHAIN_ID, false).unwrap();
+
+        // Signed for the regular exchange, not valid on neg risk.
+        assert!(verify_order(&signed_order, CHAIN_ID, true).is_err());
+
+        signed_order.order.makerAmount = U256::from(1);
+        assert!(matches!(verify_order(&signed_order, CHAIN_ID, false), Err(Error::InvalidOrderSignature)));
+    }
+
+    #[test]
+    fn test_order_hash() {
+        let order = get_order(get_signer().address());
+        assert_ne!(order_hash(&order, CHAIN_ID, false).unwrap(), order_hash(&order, CHAIN_ID, true).unwrap());
+        assert_ne!(order_hash(&order, CHAIN_ID, false).unwrap(), order_struct_hash(&order));
+        assert!(order_hash(&order, 1, false).is_err());
+    }
+
+    #[test]
+    fn test_l2_headers_redacted() {
+        let signer = get_signer();
+        let creds = ApiCreds {
+            api_key:    "00000000-0000-0000-0000-000000000000".to_string(),
+            secret:     "AAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAA=".to_string(),
+            passphrase: "correct-horse".to_string(),
+        };
+        let headers = l2_headers(&signer, &creds, &reqwest::Method::GET, "/orders", None, 1000000).unwrap();
+        let debug = format!("{:?}", headers);
+        assert!(!debug.contains(&creds.api_key));
+        assert!(!debug.contains(&creds.passphrase));
+        assert!(headers["POLY_SIGNATURE"].is_sensitive());
+
+        let debug = format!("{:?}", creds);
+        assert!(!debug.contains(&creds.api_key));
+        assert!(!debug.contains(&creds.secret));
+        assert!(!debug.contains(&creds.passphrase));
+    }
 }
\ No newline at end of file
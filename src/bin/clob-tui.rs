@@ -0,0 +1,364 @@
+mod common;
+
+use std::{collections::HashMap, time::Duration};
+use clap::Parser;
+use ratatui::{
+    crossterm::event::{self, Event, KeyCode, KeyEventKind},
+    layout::{Constraint, Layout},
+    style::{Color, Modifier, Style},
+    widgets::{Block, Paragraph, Row, Table, TableState, Tabs},
+    DefaultTerminal,
+    Frame,
+};
+use tokio::sync::mpsc;
+use clob::{
+    client::{ClobClient, OrderArgs, OrderType},
+    schema::{OpenOrder, OrderResponse, Orderbook, Side, TokenId, END_CURSOR},
+    Result,
+};
+use common::{order_units, truncate};
+
+// How long to wait for a key press before applying updates and redrawing.
+const INPUT_POLL: Duration = Duration::from_millis(100);
+
+// Live depth ladders of the tokens with our resting orders overlaid,
+// configured from the same environment variables as `ClobClient::from_env`.
+#[derive(Debug, Parser)]
+#[command(name = "clob-tui", version)]
+struct Args {
+    #[arg(required = true, help = "Token ids to show")]
+    token_ids:   Vec<TokenId>,
+    #[arg(long, default_value_t = 1000, help = "Book and open orders polling interval")]
+    interval_ms: u64,
+    #[arg(long, default_value_t = 15, help = "Levels shown per side")]
+    depth:       usize,
+}
+
+enum Update {
+    Book(TokenId, Orderbook),
+    Orders(Vec<OpenOrder>),
+    Status(String),
+}
+
+enum Mode {
+    Normal,
+    // Typing "<price> <size>" for a quick GTC order.
+    Order { buy: bool, input: String },
+}
+
+struct App {
+    client:         ClobClient,
+    token_ids:      Vec<TokenId>,
+    depth:          usize,
+    selected_token: usize,
+    books:          HashMap<TokenId, Orderbook>,
+    orders:         Vec<OpenOrder>,
+    order_state:    TableState,
+    mode:           Mode,
+    status:         String,
+    tx:             mpsc::UnboundedSender<Update>,
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    let args = Args::parse();
+    let client = ClobClient::from_env()?;
+    let (tx, rx) = mpsc::unbounded_channel();
+    tokio::spawn(poll(client.clone(), args.token_ids.clone(), Duration::from_millis(args.interval_ms), tx.clone()));
+
+    let app = App {
+        client,
+        token_ids:      args.token_ids,
+        depth:          args.depth,
+        selected_token: 0,
+        books:          HashMap::new(),
+        orders:         Vec::new(),
+        order_state:    TableState::default(),
+        mode:           Mode::Normal,
+        status:         "loading...".to_string(),
+        tx,
+    };
+
+    let terminal = ratatui::init();
+    let result = run(terminal, app, rx).await;
+    ratatui::restore();
+    result
+}
+
+async fn run(mut terminal: DefaultTerminal, mut app: App, mut rx: mpsc::UnboundedReceiver<Update>) -> Result<()> {
+    loop {
+        terminal.draw(|frame| app.draw(frame))?;
+        while let Ok(update) = rx.try_recv() {
+            app.apply(update);
+        }
+        if !tokio::task::block_in_place(|| event::poll(INPUT_POLL))? {
+            continue;
+        }
+        if let Event::Key(key) = event::read()? {
+            if key.kind == KeyEventKind::Press && !app.handle_key(key.code) {
+                return Ok(());
+            }
+        }
+    }
+}
+
+// Polls the books of all tokens and our open orders until the app exits.
+async fn poll(client: ClobClient, token_ids: Vec<TokenId>, interval: Duration, tx: mpsc::UnboundedSender<Update>) {
+    let mut ticker = tokio::time::interval(interval);
+    while !tx.is_closed() {
+        ticker.tick().await;
+        for &token_id in &token_ids {
+            let update = match client.get_market_book(&token_id.to_string()).await {
+                Ok(book) => Update::Book(token_id, book),
+                Err(e)   => Update::Status(format!("book {}: {}", token_id, e)),
+            };
+            let _ = tx.send(update);
+        }
+        let update = match get_open_orders(&client).await {
+            Ok(orders) => Update::Orders(orders),
+            Err(e)     => Update::Status(format!("open orders: {}", e)),
+        };
+        let _ = tx.send(update);
+    }
+}
+
+async fn get_open_orders(client: &ClobClient) -> Result<Vec<OpenOrder>> {
+    let mut orders = Vec::new();
+    let mut cursor = None;
+    loop {
+        let page = client.get_active_orders(cursor.as_deref(), None, None).await?;
+        orders.extend(page.data);
+        if page.next_cursor == END_CURSOR || page.next_cursor.is_empty() {
+            return Ok(orders);
+        }
+        cursor = Some(page.next_cursor);
+    }
+}
+
+async fn quick_order(client: &ClobClient, token_id: TokenId, buy: bool, price: f64, size: f64) -> Result<OrderResponse> {
+    let (price, size) = order_units(price, size)?;
+    client.post_order(OrderArgs {
+        price,
+        size,
+        buy,
+        asset_id:     token_id.to_string(),
+        neg_risk:     None,
+        fee_rate_bps: None,
+        expiration:   None,
+        type_:        OrderType::GTC,
+    }).await
+}
+
+// Price in ten thousandths, the book and orders endpoints format prices differently.
+fn price_key(price: &str) -> i64 {
+    price.parse::<f64>().map_or(0, |price| (price * 10_000.0).round() as i64)
+}
+
+fn remaining(order: &OpenOrder) -> f64 {
+    let size = order.original_size.parse::<f64>().unwrap_or(0.0);
+    size - order.size_matched.parse::<f64>().unwrap_or(0.0)
+}
+
+impl App {
+
+    fn token_id(&self) -> TokenId {
+        self.token_ids[self.selected_token]
+    }
+
+    // Our resting orders on the selected token.
+    fn token_orders(&self) -> Vec<&OpenOrder> {
+        self.orders.iter().filter(|order| order.asset_id == self.token_id()).collect()
+    }
+
+    fn apply(&mut self, update: Update) {
+        match update {
+            Update::Book(token_id, book) => {
+                self.books.insert(token_id, book);
+            }
+            Update::Orders(orders) => {
+                self.orders = orders;
+                let count = self.token_orders().len();
+                if self.order_state.selected().is_some_and(|i| i >= count) {
+                    self.order_state.select(count.checked_sub(1));
+                }
+            }
+            Update::Status(status) => self.status = status,
+        }
+    }
+
+    // Runs an action in the background, reporting its result in the status line.
+    fn spawn<F>(&self, action: F)
+    where
+        F: std::future::Future<Output = String> + Send + 'static,
+    {
+        let tx = self.tx.clone();
+        tokio::spawn(async move {
+            let _ = tx.send(Update::Status(action.await));
+        });
+    }
+
+    // Returns false to exit.
+    fn handle_key(&mut self, key: KeyCode) -> bool {
+        if let Mode::Order { buy, input } = &mut self.mode {
+            match key {
+                KeyCode::Char(c) if c.is_ascii_digit() || c == '.' || c == ' ' => input.push(c),
+                KeyCode::Backspace => {
+                    input.pop();
+                }
+                KeyCode::Enter => {
+                    let buy = *buy;
+                    let parsed = input.split_once(' ').and_then(|(price, size)| {
+                        Some((price.trim().parse::<f64>().ok()?, size.trim().parse::<f64>().ok()?))
+                    });
+                    self.mode = Mode::Normal;
+                    match parsed {
+                        Some((price, size)) => {
+                            let client = self.client.clone();
+                            let token_id = self.token_id();
+                            self.status = "placing order...".to_string();
+                            self.spawn(async move {
+                                match quick_order(&client, token_id, buy, price, size).await {
+                                    Ok(resp) => match (resp.order_id, resp.status) {
+                                        (Some(order_id), Some(status)) => format!("order {} {}", order_id, status),
+                                        _ => format!("order rejected: {}", resp.error_msg),
+                                    },
+                                    Err(e)   => format!("order failed: {}", e),
+                                }
+                            });
+                        }
+                        None => self.status = "expected \"<price> <size>\"".to_string(),
+                    }
+                }
+                KeyCode::Esc => self.mode = Mode::Normal,
+                _ => {}
+            }
+            return true;
+        }
+
+        let order_count = self.token_orders().len();
+        match key {
+            KeyCode::Char('q') | KeyCode::Esc => return false,
+            KeyCode::Tab | KeyCode::Right => {
+                self.selected_token = (self.selected_token + 1) % self.token_ids.len();
+                self.order_state.select(None);
+            }
+            KeyCode::BackTab | KeyCode::Left => {
+                self.selected_token = (self.selected_token + self.token_ids.len() - 1) % self.token_ids.len();
+                self.order_state.select(None);
+            }
+            KeyCode::Down if order_count > 0 => {
+                let i = self.order_state.selected().map_or(0, |i| (i + 1).min(order_count - 1));
+                self.order_state.select(Some(i));
+            }
+            KeyCode::Up if order_count > 0 => {
+                let i = self.order_state.selected().map_or(0, |i| i.saturating_sub(1));
+                self.order_state.select(Some(i));
+            }
+            KeyCode::Char('b') => self.mode = Mode::Order { buy: true, input: String::new() },
+            KeyCode::Char('s') => self.mode = Mode::Order { buy: false, input: String::new() },
+            KeyCode::Char('c') => {
+                let Some(order) = self.order_state.selected().and_then(|i| self.token_orders().get(i).copied()) else {
+                    self.status = "no order selected".to_string();
+                    return true;
+                };
+                let client = self.client.clone();
+                let order_id = order.id;
+                self.spawn(async move {
+                    match client.cancel_order(&order_id.to_string()).await {
+                        Ok(resp) if resp.canceled.contains(&order_id) => format!("canceled {}", order_id),
+                        Ok(resp) => format!("not canceled: {:?}", resp.not_canceled),
+                        Err(e)   => format!("cancel failed: {}", e),
+                    }
+                });
+            }
+            KeyCode::Char('x') => {
+                let order_ids = self.token_orders().iter().map(|order| order.id.to_string()).collect::<Vec<_>>();
+                if order_ids.is_empty() {
+                    return true;
+                }
+                let client = self.client.clone();
+                self.spawn(async move {
+                    match client.cancel_orders(&order_ids).await {
+                        Ok(resp) => format!("canceled {}, not canceled {}", resp.canceled.len(), resp.not_canceled.len()),
+                        Err(e)   => format!("cancel failed: {}", e),
+                    }
+                });
+            }
+            _ => {}
+        }
+        true
+    }
+
+    fn draw(&mut self, frame: &mut Frame) {
+        let [tabs_area, main_area, status_area] = Layout::vertical([
+            Constraint::Length(3),
+            Constraint::Min(0),
+            Constraint::Length(3),
+        ]).areas(frame.area());
+        let [ladder_area, orders_area] = Layout::horizontal([
+            Constraint::Percentage(55),
+            Constraint::Percentage(45),
+        ]).areas(main_area);
+
+        let tabs = Tabs::new(self.token_ids.iter().map(|id| truncate(&id.to_string(), 16)))
+            .select(self.selected_token)
+            .highlight_style(Style::new().add_modifier(Modifier::REVERSED))
+            .block(Block::bordered().title("tokens (tab)"));
+        frame.render_widget(tabs, tabs_area);
+
+        frame.render_widget(self.ladder(), ladder_area);
+
+        let header = Row::new(["side", "price", "remaining", "id"]).style(Style::new().add_modifier(Modifier::BOLD));
+        let rows = self.token_orders().into_iter().map(|order| Row::new([
+            order.side.to_string(),
+            order.price.clone(),
+            format!("{:.2}", remaining(order)),
+            truncate(&order.id.to_string(), 18),
+        ])).collect::<Vec<_>>();
+        let orders = Table::new(rows, [Constraint::Length(5), Constraint::Length(7), Constraint::Length(10), Constraint::Min(10)])
+            .header(header)
+            .row_highlight_style(Style::new().add_modifier(Modifier::REVERSED))
+            .block(Block::bordered().title("our orders (up/down, c cancel, x cancel all)"));
+        frame.render_stateful_widget(orders, orders_area, &mut self.order_state);
+
+        let status = match &self.mode {
+            Mode::Order { buy, input } => format!("{} <price> <size>: {}_", if *buy { "buy" } else { "sell" }, input),
+            Mode::Normal => format!("{}  |  b buy, s sell, q quit", self.status),
+        };
+        frame.render_widget(Paragraph::new(status).block(Block::bordered()), status_area);
+    }
+
+    // Asks above bids, best levels in the middle, with the size of our orders at each level.
+    fn ladder(&self) -> Table<'static> {
+        let mut ours: HashMap<(bool, i64), f64> = HashMap::new();
+        for order in self.token_orders() {
+            *ours.entry((order.side == Side::Buy, price_key(&order.price))).or_default() += remaining(order);
+        }
+
+        let level = |price: &str, size: &str, buy: bool| {
+            let our_size = ours.get(&(buy, price_key(price))).copied();
+            let our_cell = our_size.map(|size| format!("{:.2}", size)).unwrap_or_default();
+            let cells = if buy {
+                [our_cell, size.to_string(), price.to_string(), String::new(), String::new()]
+            } else {
+                [String::new(), String::new(), price.to_string(), size.to_string(), our_cell]
+            };
+            let color = if buy { Color::Green } else { Color::Red };
+            let style = if our_size.is_some() { Style::new().fg(color).add_modifier(Modifier::BOLD) } else { Style::new().fg(color) };
+            Row::new(cells).style(style)
+        };
+
+        // Both sides are sorted with the best level last.
+        let mut rows = Vec::new();
+        if let Some(book) = self.books.get(&self.token_id()) {
+            let asks = &book.asks[book.asks.len().saturating_sub(self.depth)..];
+            rows.extend(asks.iter().map(|ask| level(&ask.price, &ask.size, false)));
+            rows.extend(book.bids.iter().rev().take(self.depth).map(|bid| level(&bid.price, &bid.size, true)));
+        }
+
+        let header = Row::new(["ours", "bid", "price", "ask", "ours"]).style(Style::new().add_modifier(Modifier::BOLD));
+        Table::new(rows, [Constraint::Ratio(1, 5); 5])
+            .header(header)
+            .block(Block::bordered().title("book"))
+    }
+}
@@ -0,0 +1,403 @@
+mod common;
+
+use std::{fmt::Display, process::ExitCode};
+use alloy::primitives::{utils::format_units, U256};
+use clap::{Parser, Subcommand, ValueEnum};
+use serde::Serialize;
+use clob::{
+    catalog::{MarketCatalog, MarketFilter},
+    client::{ClobClient, OrderArgs, OrderType},
+    schema::{AssetType, Market, OrderResponse, END_CURSOR},
+    Result,
+};
+use common::{order_units, truncate};
+
+// Longest question shown in tables.
+const MAX_QUESTION_LEN: usize = 60;
+
+// Everyday CLOB operations, configured from the same environment variables as `ClobClient::from_env`.
+#[derive(Debug, Parser)]
+#[command(name = "clob", version)]
+struct Cli {
+    #[arg(long, short, value_enum, global = true, default_value_t = Format::Table)]
+    output:  Format,
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum Format {
+    Json,
+    Table,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    #[command(subcommand, about = "Markets")]
+    Markets(MarketsCommand),
+    #[command(about = "Order book of a token")]
+    Book {
+        token_id: String,
+        #[arg(long, default_value_t = 10, help = "Levels shown per side in tables")]
+        depth:    usize,
+    },
+    #[command(about = "Sign and post an order")]
+    Place {
+        #[arg(long)]
+        token_id:     String,
+        #[arg(long, value_enum)]
+        side:         Side,
+        #[arg(long, help = "Price in units of implied probability, e.g. 0.55")]
+        price:        f64,
+        #[arg(long, help = "Size in outcome tokens")]
+        size:         f64,
+        #[arg(long, value_enum, default_value_t = Type::Gtc)]
+        r#type:       Type,
+        #[arg(long, help = "UTC seconds timestamp, required for GTD orders")]
+        expiration:   Option<u64>,
+        #[arg(long, help = "Fee rate in bps, the market's base fee if unset")]
+        fee_rate_bps: Option<u32>,
+    },
+    #[command(about = "Sign and post a batch of orders from a JSON or TOML file")]
+    PlaceBatch {
+        file: std::path::PathBuf,
+    },
+    #[command(about = "Cancel orders by id, or all orders of a market or account")]
+    Cancel {
+        order_ids: Vec<String>,
+        #[arg(long, conflicts_with_all = ["order_ids", "all"])]
+        market:    Option<String>,
+        #[arg(long, conflicts_with = "order_ids")]
+        all:       bool,
+    },
+    #[command(about = "Open orders, optionally of a market or token")]
+    Orders {
+        #[arg(long)]
+        market:   Option<String>,
+        #[arg(long)]
+        token_id: Option<String>,
+    },
+    #[command(subcommand, about = "API keys")]
+    Keys(KeysCommand),
+    #[command(about = "USDC balance, or the balance of an outcome token")]
+    Balance {
+        #[arg(long)]
+        token_id: Option<String>,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+enum MarketsCommand {
+    #[command(about = "A page of markets")]
+    List {
+        #[arg(long)]
+        cursor: Option<String>,
+    },
+    #[command(
+        about = "Markets whose question, slug, tags or outcomes contain every word of the query, case insensitive",
+        long_about = "Markets whose question, slug, tags or outcomes contain every word of the query, case insensitive. \
+                      Every page of markets is fetched before searching, so this takes a while."
+    )]
+    Search {
+        query:          String,
+        #[arg(long, default_value_t = 20)]
+        limit:          usize,
+        #[arg(long, help = "Include closed markets")]
+        include_closed: bool,
+        #[arg(long, help = "Only markets with the tag")]
+        tag:            Option<String>,
+        #[arg(long, help = "Only neg risk markets")]
+        neg_risk:       bool,
+        #[arg(long, help = "Only markets with liquidity rewards")]
+        rewards:        bool,
+    },
+    #[command(about = "A market by condition id")]
+    Get {
+        condition_id: String,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+enum KeysCommand {
+    #[command(about = "API keys of the signer")]
+    List,
+    #[command(about = "Create new creds, nonces reuse is an error")]
+    Create {
+        #[arg(long, default_value_t = 0)]
+        nonce: u64,
+    },
+    #[command(about = "Derive the creds previously created with the nonce")]
+    Derive {
+        #[arg(long, default_value_t = 0)]
+        nonce: u64,
+    },
+    #[command(about = "Delete the API key of the configured creds")]
+    Delete,
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum Side {
+    Buy,
+    Sell,
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum Type {
+    Gtc,
+    Fok,
+    Gtd,
+}
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    let cli = Cli::parse();
+    match run(cli).await {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+async fn run(cli: Cli) -> Result<()> {
+    let client = ClobClient::from_env()?;
+    let format = cli.output;
+
+    match cli.command {
+        Command::Markets(MarketsCommand::List { cursor }) => {
+            let markets = client.get_markets(cursor.as_deref()).await?;
+            warn_malformed(markets.errors.len());
+            emit(format, &markets, || {
+                println!("next cursor: {}", markets.next_cursor);
+                markets_table(&markets.data)
+            })
+        }
+        Command::Markets(MarketsCommand::Search { query, limit, include_closed, tag, neg_risk, rewards }) => {
+            let catalog = MarketCatalog::<Market>::load(&client).await?;
+            warn_malformed(catalog.errors.len());
+            let filter = MarketFilter {
+                closed:   (!include_closed).then_some(false),
+                neg_risk: neg_risk.then_some(true),
+                rewards:  rewards.then_some(true),
+                tag,
+                ..Default::default()
+            };
+            let markets: Vec<&Market> = catalog.search(&query, &filter).into_iter().take(limit).collect();
+            emit(format, &markets, || markets_table(markets.iter().copied()))
+        }
+        Command::Markets(MarketsCommand::Get { condition_id }) => {
+            let market = client.get_market(&condition_id).await?;
+            emit(format, &market, || markets_table(std::slice::from_ref(&market)))
+        }
+        Command::Book { token_id, depth } => {
+            let book = client.get_market_book(&token_id).await?;
+            emit(format, &book, || {
+                // Best levels are last in the response.
+                let asks = book.asks.iter().rev().take(depth).rev().map(|level| row([&"ask", &level.price, &level.size]));
+                let bids = book.bids.iter().rev().take(depth).map(|level| row([&"bid", &level.price, &level.size]));
+                (vec!["side", "price", "size"], asks.chain(bids).collect())
+            })
+        }
+        Command::Place { token_id, side, price, size, r#type, expiration, fee_rate_bps } => {
+            let (price, size) = order_units(price, size)?;
+            let args = OrderArgs {
+                price,
+                size,
+                buy:          matches!(side, Side::Buy),
+                asset_id:     token_id,
+                neg_risk:     None,
+                fee_rate_bps,
+                expiration,
+                type_:        match r#type {
+                    Type::Gtc => OrderType::GTC,
+                    Type::Fok => OrderType::FOK,
+                    Type::Gtd => OrderType::GTD,
+                },
+            };
+            let resp = client.post_order(args).await?;
+            emit(format, &resp, || (
+                vec!["order id", "status", "success", "error"],
+                vec![row([&display_opt(resp.order_id), &display_opt(resp.status), &resp.success, &resp.error_msg])],
+            ))
+        }
+        Command::PlaceBatch { file } => {
+            // Every order is posted even if one fails, so the output shows which are live.
+            let mut results = Vec::new();
+            let mut failed = None;
+            for args in OrderArgs::load_batch(file)? {
+                match client.post_order(args).await {
+                    Ok(resp) => results.push(Posted::Ok(resp)),
+                    Err(e)   => {
+                        results.push(Posted::Err { error: e.to_string() });
+                        failed.get_or_insert(e);
+                    }
+                }
+            }
+            emit(format, &results, || (
+                vec!["order id", "status", "success", "error"],
+                results.iter().map(|result| match result {
+                    Posted::Ok(resp)      => row([&display_opt(resp.order_id), &display_opt(resp.status), &resp.success, &resp.error_msg]),
+                    Posted::Err { error } => row([&"", &"", &false, error]),
+                }).collect(),
+            ))?;
+            failed.map_or(Ok(()), Err)
+        }
+        Command::Cancel { order_ids, market, all } => {
+            let resp = match (market, all) {
+                (Some(market), _) => client.cancel_orders_in_market(&market).await?,
+                (None, true)      => client.cancel_all().await?,
+                (None, false)     => client.cancel_orders(&order_ids).await?,
+            };
+            emit(format, &resp, || {
+                let canceled = resp.canceled.iter().map(|id| row([id, &"canceled"]));
+                let not_canceled = resp.not_canceled.iter().map(|(id, reason)| row([id, reason]));
+                (vec!["order id", "result"], canceled.chain(not_canceled).collect())
+            })
+        }
+        Command::Orders { market, token_id } => {
+            let mut orders = Vec::new();
+            let mut cursor = None;
+            loop {
+                let page = client.get_active_orders(cursor.as_deref(), token_id.as_deref(), market.as_deref()).await?;
+                orders.extend(page.data);
+                if page.next_cursor == END_CURSOR || page.next_cursor.is_empty() {
+                    break;
+                }
+                cursor = Some(page.next_cursor);
+            }
+            emit(format, &orders, || (
+                vec!["order id", "side", "price", "size", "matched", "outcome", "token id"],
+                orders.iter().map(|order| row([
+                    &order.id,
+                    &order.side,
+                    &order.price,
+                    &order.original_size,
+                    &order.size_matched,
+                    &order.outcome,
+                    &order.asset_id,
+                ])).collect(),
+            ))
+        }
+        Command::Keys(KeysCommand::List) => {
+            let keys = client.get_api_keys().await?;
+            emit(format, &keys, || (vec!["api key"], keys.iter().map(|key| row([key])).collect()))
+        }
+        Command::Keys(KeysCommand::Create { nonce }) => {
+            let creds = client.create_creds_with_nonce(U256::from(nonce)).await?;
+            // Shown in full, these are what the caller asked for.
+            emit(format, &creds, || (
+                vec!["api key", "secret", "passphrase"],
+                vec![row([&creds.api_key, &creds.secret, &creds.passphrase])],
+            ))
+        }
+        Command::Keys(KeysCommand::Derive { nonce }) => {
+            let creds = client.derive_creds_from_nonce(U256::from(nonce)).await?;
+            emit(format, &creds, || (
+                vec!["api key", "secret", "passphrase"],
+                vec![row([&creds.api_key, &creds.secret, &creds.passphrase])],
+            ))
+        }
+        Command::Keys(KeysCommand::Delete) => {
+            client.delete_api_key().await?;
+            emit(format, &"OK", || (vec!["result"], vec![row([&"deleted"])]))
+        }
+        Command::Balance { token_id } => {
+            let asset_type = if token_id.is_some() { AssetType::Conditional } else { AssetType::Collateral };
+            let balance = client.get_balance_allowance(asset_type, token_id.as_deref()).await?;
+            emit(format, &balance, || {
+                let asset = token_id.as_deref().unwrap_or("USDC");
+                (vec!["asset", "balance"], vec![row([&asset, &to_units(&balance.balance)])])
+            })
+        }
+    }
+}
+
+fn warn_malformed(count: usize) {
+    if count > 0 {
+        eprintln!("skipped {} malformed markets", count);
+    }
+}
+
+type Table = (Vec<&'static str>, Vec<Vec<String>>);
+
+// Outcome of one order of a batch, the error if it couldn't be posted.
+#[derive(Serialize)]
+#[serde(untagged)]
+enum Posted {
+    Ok(OrderResponse),
+    Err { error: String },
+}
+
+fn emit<T: Serialize>(format: Format, value: &T, table: impl FnOnce() -> Table) -> Result<()> {
+    match format {
+        Format::Json => println!("{}", serde_json::to_string_pretty(value)?),
+        Format::Table => {
+            let (headers, rows) = table();
+            print_table(&headers, &rows);
+        }
+    }
+    Ok(())
+}
+
+fn row<const N: usize>(cells: [&dyn Display; N]) -> Vec<String> {
+    cells.iter().map(|cell| cell.to_string()).collect()
+}
+
+fn markets_table<'a>(markets: impl IntoIterator<Item = &'a Market>) -> Table {
+    (
+        vec!["condition id", "question", "outcomes", "open", "neg risk"],
+        markets.into_iter().map(|market| {
+            let outcomes = market.tokens
+                .iter()
+                .map(|token| format!("{} {}", token.outcome, token.price))
+                .collect::<Vec<_>>()
+                .join(" / ");
+            let open = market.active && !market.closed && market.accepting_orders;
+            row([
+                &market.condition_id,
+                &truncate(&market.question, MAX_QUESTION_LEN),
+                &outcomes,
+                &open.to_string(),
+                &market.neg_risk.to_string(),
+            ])
+        }).collect(),
+    )
+}
+
+fn display_opt(value: Option<impl Display>) -> String {
+    value.map(|value| value.to_string()).unwrap_or_default()
+}
+
+// Base units to whole tokens, both USDC and outcome tokens have 6 decimals.
+fn to_units(amount: &str) -> String {
+    amount.parse::<U256>()
+        .ok()
+        .and_then(|amount| format_units(amount, 6).ok())
+        .unwrap_or_else(|| amount.to_string())
+}
+
+fn print_table(headers: &[&str], rows: &[Vec<String>]) {
+    let mut widths = headers.iter().map(|header| header.len()).collect::<Vec<_>>();
+    for row in rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+
+    let print_row = |cells: Vec<&str>| {
+        let line = cells
+            .iter()
+            .zip(&widths)
+            .map(|(cell, width)| format!("{:<width$}", cell, width = width))
+            .collect::<Vec<_>>()
+            .join("  ");
+        println!("{}", line.trim_end());
+    };
+
+    print_row(headers.to_vec());
+    print_row(widths.iter().map(|width| "-".repeat(*width)).collect::<Vec<_>>().iter().map(String::as_str).collect());
+    for row in rows {
+        print_row(row.iter().map(String::as_str).collect());
+    }
+}
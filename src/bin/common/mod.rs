@@ -0,0 +1,24 @@
+// Helpers shared by the binaries.
+
+use clob::{Error, Result};
+
+// Price and size of an order as `OrderArgs` thousandths and hundredths.
+pub fn order_units(price: f64, size: f64) -> Result<(u32, u32)> {
+    if price.is_nan() || price <= 0.0 || price >= 1.0 {
+        return Err(Error::InvalidPrice);
+    }
+    let size = (size * 100.0).round();
+    if size.is_nan() || size < 1.0 || size > u32::MAX as f64 {
+        return Err(Error::InvalidOrderSize);
+    }
+    Ok(((price * 1000.0).round() as u32, size as u32))
+}
+
+// Cuts the string to at most `max` chars, ending in an ellipsis if cut.
+pub fn truncate(s: &str, max: usize) -> String {
+    if s.chars().count() <= max {
+        s.to_string()
+    } else {
+        format!("{}...", s.chars().take(max - 3).collect::<String>())
+    }
+}
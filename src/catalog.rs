@@ -0,0 +1,360 @@
+use std::{collections::HashMap, future::Future};
+use chrono::{DateTime, Utc};
+use serde::de::DeserializeOwned;
+use crate::{
+    client::ClobClient,
+    schema::{ConditionId, Market, MarketError, Markets, Rewards, SimplifiedMarketResponse, Token, TokenId, END_CURSOR},
+    Result,
+};
+
+// Markets a catalog can hold, full or simplified. Fields a schema lacks are none.
+pub trait CatalogMarket: DeserializeOwned + Send {
+    fn fetch_page(client: &ClobClient, cursor: Option<&str>) -> impl Future<Output = Result<Markets<Self>>> + Send;
+    fn condition_id(&self) -> ConditionId;
+    fn tokens(&self) -> &[Token];
+    fn rewards(&self) -> &Rewards;
+    fn active(&self) -> bool;
+    fn accepting_orders(&self) -> bool;
+    fn closed(&self) -> Option<bool>;
+    fn neg_risk(&self) -> Option<bool>;
+    fn end_date(&self) -> Option<DateTime<Utc>>;
+    fn question(&self) -> Option<&str>;
+    fn slug(&self) -> Option<&str>;
+    fn tags(&self) -> &[String];
+}
+
+impl CatalogMarket for Market {
+
+    fn fetch_page(client: &ClobClient, cursor: Option<&str>) -> impl Future<Output = Result<Markets<Self>>> + Send {
+        client.get_markets(cursor)
+    }
+
+    fn condition_id(&self) -> ConditionId { self.condition_id }
+    fn tokens(&self) -> &[Token] { &self.tokens }
+    fn rewards(&self) -> &Rewards { &self.rewards }
+    fn active(&self) -> bool { self.active }
+    fn accepting_orders(&self) -> bool { self.accepting_orders }
+    fn closed(&self) -> Option<bool> { Some(self.closed) }
+    fn neg_risk(&self) -> Option<bool> { Some(self.neg_risk) }
+    fn end_date(&self) -> Option<DateTime<Utc>> { self.end_date_iso }
+    fn question(&self) -> Option<&str> { Some(&self.question) }
+    fn slug(&self) -> Option<&str> { Some(&self.market_slug) }
+    fn tags(&self) -> &[String] { self.tags.as_deref().unwrap_or_default() }
+}
+
+impl CatalogMarket for SimplifiedMarketResponse {
+
+    fn fetch_page(client: &ClobClient, cursor: Option<&str>) -> impl Future<Output = Result<Markets<Self>>> + Send {
+        client.get_simplified_markets(cursor)
+    }
+
+    fn condition_id(&self) -> ConditionId { self.condition_id }
+    fn tokens(&self) -> &[Token] { &self.tokens }
+    fn rewards(&self) -> &Rewards { &self.rewards }
+    fn active(&self) -> bool { self.active }
+    fn accepting_orders(&self) -> bool { self.accepting_orders }
+    fn closed(&self) -> Option<bool> { None }
+    fn neg_risk(&self) -> Option<bool> { None }
+    fn end_date(&self) -> Option<DateTime<Utc>> { None }
+    fn question(&self) -> Option<&str> { None }
+    fn slug(&self) -> Option<&str> { None }
+    fn tags(&self) -> &[String] { &[] }
+}
+
+// Conditions a market must meet, unset fields match any market.
+// A market lacking a field, e.g. `closed` of simplified markets, doesn't match a filter on it.
+#[derive(Debug, Clone, Default)]
+pub struct MarketFilter {
+    pub active:           Option<bool>,
+    pub accepting_orders: Option<bool>,
+    pub closed:           Option<bool>,
+    pub neg_risk:         Option<bool>,
+    pub rewards:          Option<bool>,
+    // Case insensitive.
+    pub tag:              Option<String>,
+    // Inclusive end date window.
+    pub ends_after:       Option<DateTime<Utc>>,
+    pub ends_before:      Option<DateTime<Utc>>,
+}
+
+impl MarketFilter {
+
+    pub fn matches<T: CatalogMarket>(&self, market: &T) -> bool {
+        fn check<V: PartialEq>(want: Option<V>, have: Option<V>) -> bool {
+            want.is_none() || want == have
+        }
+        check(self.active, Some(market.active()))
+            && check(self.accepting_orders, Some(market.accepting_orders()))
+            && check(self.closed, market.closed())
+            && check(self.neg_risk, market.neg_risk())
+            && check(self.rewards, Some(market.rewards().enabled()))
+            && self.tag.as_ref().is_none_or(|tag| market.tags().iter().any(|t| t.eq_ignore_ascii_case(tag)))
+            && self.ends_after.is_none_or(|after| market.end_date().is_some_and(|end| end >= after))
+            && self.ends_before.is_none_or(|before| market.end_date().is_some_and(|end| end <= before))
+    }
+}
+
+// All markets in memory, indexed by condition id, token id, slug and tag.
+#[derive(Debug)]
+pub struct MarketCatalog<T: CatalogMarket = Market> {
+    markets:      Vec<T>,
+    // Lowercase question, slug, tags and outcomes of each market.
+    search_text:  Vec<String>,
+    by_condition: HashMap<ConditionId, usize>,
+    by_token:     HashMap<TokenId, usize>,
+    by_slug:      HashMap<String, usize>,
+    // Keyed by lowercase tag.
+    by_tag:       HashMap<String, Vec<usize>>,
+    // Cursor of the last page, which new markets are appended to.
+    cursor:       Option<String>,
+    // Markets of the loaded pages that failed to deserialize.
+    pub errors:   Vec<MarketError>,
+}
+
+impl<T: CatalogMarket> Default for MarketCatalog<T> {
+    fn default() -> Self {
+        Self {
+            markets:      Vec::new(),
+            search_text:  Vec::new(),
+            by_condition: HashMap::new(),
+            by_token:     HashMap::new(),
+            by_slug:      HashMap::new(),
+            by_tag:       HashMap::new(),
+            cursor:       None,
+            errors:       Vec::new(),
+        }
+    }
+}
+
+impl<T: CatalogMarket> MarketCatalog<T> {
+
+    // Pages through all markets.
+    pub async fn load(client: &ClobClient) -> Result<Self> {
+        let mut catalog = Self::default();
+        catalog.refresh(client).await?;
+        Ok(catalog)
+    }
+
+    // Fetches from the last page loaded onwards, adding new markets and updating those already known.
+    // Returns the number of markets added. Markets on earlier pages are only updated by `upsert`.
+    pub async fn refresh(&mut self, client: &ClobClient) -> Result<usize> {
+        let before = self.markets.len();
+        let mut cursor = self.cursor.clone();
+        loop {
+            let page = T::fetch_page(client, cursor.as_deref()).await?;
+            self.errors.extend(page.errors);
+            for market in page.data {
+                self.upsert(market);
+            }
+            if page.next_cursor == END_CURSOR || page.next_cursor.is_empty() {
+                break;
+            }
+            cursor = Some(page.next_cursor);
+        }
+        // The last page may fill up, so it is fetched again next time.
+        self.cursor = cursor;
+        Ok(self.markets.len() - before)
+    }
+
+    // Adds a market or replaces the one with the same condition id.
+    pub fn upsert(&mut self, market: T) {
+        let index = match self.by_condition.get(&market.condition_id()) {
+            Some(&index) => {
+                self.unindex(index);
+                self.markets[index] = market;
+                index
+            }
+            None => {
+                self.markets.push(market);
+                self.search_text.push(String::new());
+                self.markets.len() - 1
+            }
+        };
+        self.index(index);
+    }
+
+    fn index(&mut self, index: usize) {
+        let market = &self.markets[index];
+        self.by_condition.insert(market.condition_id(), index);
+        for token in market.tokens() {
+            self.by_token.insert(token.token_id, index);
+        }
+        if let Some(slug) = market.slug() {
+            self.by_slug.insert(slug.to_string(), index);
+        }
+        for tag in market.tags() {
+            self.by_tag.entry(tag.to_lowercase()).or_default().push(index);
+        }
+
+        let mut text = Vec::new();
+        text.extend(market.question());
+        text.extend(market.slug());
+        text.extend(market.tags().iter().map(String::as_str));
+        text.extend(market.tokens().iter().map(|token| token.outcome.as_str()));
+        self.search_text[index] = text.join("\n").to_lowercase();
+    }
+
+    fn unindex(&mut self, index: usize) {
+        let market = &self.markets[index];
+        for token in market.tokens() {
+            self.by_token.remove(&token.token_id);
+        }
+        if let Some(slug) = market.slug() {
+            self.by_slug.remove(slug);
+        }
+        for tag in market.tags() {
+            if let Some(indices) = self.by_tag.get_mut(&tag.to_lowercase()) {
+                indices.retain(|&i| i != index);
+            }
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.markets.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.markets.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        self.markets.iter()
+    }
+
+    pub fn get(&self, condition_id: ConditionId) -> Option<&T> {
+        self.by_condition.get(&condition_id).map(|&i| &self.markets[i])
+    }
+
+    // Market the token belongs to.
+    pub fn by_token(&self, token_id: TokenId) -> Option<&T> {
+        self.by_token.get(&token_id).map(|&i| &self.markets[i])
+    }
+
+    pub fn by_slug(&self, slug: &str) -> Option<&T> {
+        self.by_slug.get(slug).map(|&i| &self.markets[i])
+    }
+
+    // Case insensitive.
+    pub fn by_tag(&self, tag: &str) -> Vec<&T> {
+        self.by_tag
+            .get(&tag.to_lowercase())
+            .map(|indices| indices.iter().map(|&i| &self.markets[i]).collect())
+            .unwrap_or_default()
+    }
+
+    pub fn filter(&self, filter: &MarketFilter) -> Vec<&T> {
+        self.markets.iter().filter(|market| filter.matches(*market)).collect()
+    }
+
+    // Markets matching the filter whose question, slug, tags or outcomes contain every word of
+    // the query, case insensitive. Markets with more words in the question come first.
+    pub fn search(&self, query: &str, filter: &MarketFilter) -> Vec<&T> {
+        let words: Vec<String> = query.split_whitespace().map(str::to_lowercase).collect();
+        let mut found: Vec<(usize, &T)> = self.markets
+            .iter()
+            .zip(&self.search_text)
+            .filter(|(market, text)| words.iter().all(|word| text.contains(word)) && filter.matches(*market))
+            .map(|(market, _)| {
+                let question = market.question().unwrap_or_default().to_lowercase();
+                (words.iter().filter(|word| question.contains(word.as_str())).count(), market)
+            })
+            .collect();
+        // Stable, so ties keep catalog order.
+        found.sort_by_key(|(matches, _)| std::cmp::Reverse(*matches));
+        found.into_iter().map(|(_, market)| market).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy::primitives::{B256, U256};
+    use tokio::{io::{AsyncReadExt, AsyncWriteExt}, net::TcpListener};
+    use crate::schema::get_market;
+    use super::*;
+
+    // Stand-in markets endpoint, answers a single request with an empty last page and returns its request line.
+    async fn serve_last_page(listener: TcpListener) -> String {
+        let (mut stream, _) = listener.accept().await.unwrap();
+        let mut buf = vec![0u8; 4096];
+        let mut len = 0;
+        while !buf[..len].ends_with(b"\r\n\r\n") {
+            len += stream.read(&mut buf[len..]).await.unwrap();
+        }
+        let req = String::from_utf8_lossy(&buf[..len]).to_string();
+
+        let resp_body = format!("{{\"limit\":0,\"count\":0,\"next_cursor\":\"{}\",\"data\":[]}}", END_CURSOR);
+        let resp = format!(
+            "HTTP/1.1 200 OK\r\ncontent-type: application/json\r\ncontent-length: {}\r\n\r\n{}",
+            resp_body.len(),
+            resp_body,
+        );
+        stream.write_all(resp.as_bytes()).await.unwrap();
+        req.lines().next().unwrap().to_string()
+    }
+
+    fn get_catalog() -> MarketCatalog {
+        let mut rain = get_market();
+        rain.tags = Some(vec!["Weather".to_string()]);
+        rain.end_date_iso = DateTime::from_timestamp(2_000_000, 0);
+
+        let mut election = get_market();
+        election.condition_id = ConditionId(B256::repeat_byte(2));
+        election.tokens[0].token_id = TokenId(U256::from(3));
+        election.tokens[1].token_id = TokenId(U256::from(4));
+        election.question = "Will the incumbent win the election?".to_string();
+        election.market_slug = "incumbent-wins".to_string();
+        election.tags = Some(vec!["Politics".to_string(), "Elections".to_string()]);
+        election.neg_risk = false;
+        election.closed = true;
+
+        let mut catalog = MarketCatalog::default();
+        catalog.upsert(rain);
+        catalog.upsert(election);
+        catalog
+    }
+
+    #[test]
+    fn test_catalog_indexes() {
+        let mut catalog = get_catalog();
+        assert_eq!(catalog.len(), 2);
+        assert_eq!(catalog.by_token(TokenId(U256::from(4))).unwrap().market_slug, "incumbent-wins");
+        assert_eq!(catalog.get(ConditionId(B256::repeat_byte(1))).unwrap().question, "Will it rain?");
+        assert_eq!(catalog.by_tag("politics").len(), 1);
+
+        // Replacing a market reindexes it.
+        let mut updated = get_market();
+        updated.market_slug = "rain-tomorrow".to_string();
+        catalog.upsert(updated);
+        assert_eq!(catalog.len(), 2);
+        assert!(catalog.by_slug("will-it-rain").is_none());
+        assert!(catalog.by_slug("rain-tomorrow").is_some());
+        assert!(catalog.by_tag("weather").is_empty());
+    }
+
+    #[test]
+    fn test_catalog_search_and_filter() {
+        let catalog = get_catalog();
+        assert_eq!(catalog.search("WIN election", &MarketFilter::default()).len(), 1);
+        assert_eq!(catalog.search("will", &MarketFilter::default()).len(), 2);
+        assert!(catalog.search("win", &MarketFilter { closed: Some(false), ..Default::default() }).is_empty());
+
+        let filter = MarketFilter { neg_risk: Some(true), tag: Some("weather".to_string()), ..Default::default() };
+        assert_eq!(catalog.filter(&filter).len(), 1);
+        let window = MarketFilter { ends_before: DateTime::from_timestamp(1_000_000, 0), ..Default::default() };
+        assert!(catalog.filter(&window).is_empty());
+        let window = MarketFilter { ends_after: DateTime::from_timestamp(1_000_000, 0), ..Default::default() };
+        assert_eq!(catalog.filter(&window).len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_load_from_first_page() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let server = tokio::spawn(serve_last_page(listener));
+
+        let catalog = MarketCatalog::<Market>::load(&ClobClient::new(&url)).await.unwrap();
+        assert!(catalog.is_empty());
+        // The start cursor, url encoded.
+        assert!(server.await.unwrap().starts_with("GET /markets?next_cursor=MA%3D%3D "));
+    }
+}
@@ -0,0 +1,137 @@
+use alloy::primitives::{Address, TxHash, U256};
+use serde::{Deserialize, Serialize};
+use crate::{abi::{IERC1155, IERC20}, contracts::ContractConfig, Result};
+use super::{confirm_tx, ClobClient};
+
+// Allowances below this are topped back up to the max.
+const MIN_ALLOWANCE: U256 = U256::from_limbs([0, 0, 0, 1 << 63]);
+
+// Approval state of one of the contracts that moves funds when trading.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Approval {
+    pub spender:                     Address,
+    // USDC the spender may transfer.
+    pub collateral_allowance:        U256,
+    // Whether the spender may transfer outcome tokens, none if it only takes collateral.
+    pub conditional_tokens_approved: Option<bool>,
+}
+
+impl Approval {
+    pub fn is_approved(&self) -> bool {
+        self.collateral_allowance >= MIN_ALLOWANCE && self.conditional_tokens_approved != Some(false)
+    }
+}
+
+// Contracts that move the funding address's funds when trading, splitting and merging,
+// and whether they move outcome tokens as well as collateral.
+// The conditional tokens contract pulls collateral when splitting outside neg risk markets.
+fn spenders(contracts: &ContractConfig) -> Vec<(Address, bool)> {
+    vec![
+        (contracts.exchange,           true),
+        (contracts.neg_risk_exchange,  true),
+        (contracts.neg_risk_adapter,   true),
+        (contracts.conditional_tokens, false),
+    ]
+}
+
+impl ClobClient {
+
+    // Approvals of the funding address, which is the proxy if one is set.
+    pub async fn get_approvals(&self) -> Result<Vec<Approval>> {
+        let provider = self.get_provider()?;
+        let contracts = self.get_contracts()?;
+        let owner = self.proxy.as_ref().map_or(self.get_signer()?.address(), |p| p.address);
+
+        let collateral = IERC20::new(contracts.collateral, &provider);
+        let conditional_tokens = IERC1155::new(contracts.conditional_tokens, &provider);
+
+        let mut approvals = Vec::new();
+        for (spender, moves_tokens) in spenders(contracts) {
+            let conditional_tokens_approved = if moves_tokens {
+                Some(conditional_tokens.isApprovedForAll(owner, spender).call().await?._0)
+            } else {
+                None
+            };
+            approvals.push(Approval {
+                spender,
+                collateral_allowance: collateral.allowance(owner, spender).call().await?._0,
+                conditional_tokens_approved,
+            });
+        }
+        Ok(approvals)
+    }
+
+    // Sends approval transactions for any spender missing one, waiting for each to be mined.
+    // Returns the hashes of the transactions sent, empty if already approved.
+    pub async fn set_approvals(&self) -> Result<Vec<TxHash>> {
+        self.require_eoa()?;
+
+        let provider = self.get_provider()?;
+        let contracts = self.get_contracts()?;
+        let collateral = IERC20::new(contracts.collateral, &provider);
+        let conditional_tokens = IERC1155::new(contracts.conditional_tokens, &provider);
+
+        let mut tx_hashes = Vec::new();
+        for approval in self.get_approvals().await? {
+            if approval.collateral_allowance < MIN_ALLOWANCE {
+                let pending = collateral.approve(approval.spender, U256::MAX).send().await?;
+                tx_hashes.push(confirm_tx(pending).await?);
+            }
+
+            if approval.conditional_tokens_approved == Some(false) {
+                let pending = conditional_tokens.setApprovalForAll(approval.spender, true).send().await?;
+                tx_hashes.push(confirm_tx(pending).await?);
+            }
+        }
+        Ok(tx_hashes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy::node_bindings::Anvil;
+    use crate::{client::ClobClient, contracts::MATIC_CONTRACTS};
+    use super::*;
+
+    #[test]
+    fn test_is_approved() {
+        let approval = Approval {
+            spender:                     MATIC_CONTRACTS.exchange,
+            collateral_allowance:        U256::MAX,
+            conditional_tokens_approved: Some(true),
+        };
+        assert!(approval.is_approved());
+        assert!(!Approval { conditional_tokens_approved: Some(false), ..approval }.is_approved());
+        // Collateral only spenders.
+        assert!(Approval { conditional_tokens_approved: None, ..approval }.is_approved());
+        // Spent down below the threshold.
+        assert!(!Approval { collateral_allowance: MIN_ALLOWANCE - U256::from(1), ..approval }.is_approved());
+        assert!(Approval { collateral_allowance: MIN_ALLOWANCE, ..approval }.is_approved());
+    }
+
+    #[test]
+    fn test_spenders() {
+        assert_eq!(spenders(&MATIC_CONTRACTS), vec![
+            (MATIC_CONTRACTS.exchange,           true),
+            (MATIC_CONTRACTS.neg_risk_exchange,  true),
+            (MATIC_CONTRACTS.neg_risk_adapter,   true),
+            (MATIC_CONTRACTS.conditional_tokens, false),
+        ]);
+    }
+
+    // Runs against a local anvil fork of Polygon, requires `anvil` and `POLYGON_RPC_URL`.
+    #[tokio::test]
+    #[ignore]
+    async fn test_set_approvals_anvil_fork() {
+        let anvil = Anvil::new().fork(std::env::var("POLYGON_RPC_URL").unwrap()).spawn();
+        let private_key = alloy::hex::encode_prefixed(anvil.keys()[0].to_bytes());
+        let client = ClobClient::new("http://localhost")
+            .with_signer(&private_key, 137).unwrap()
+            .with_rpc(&anvil.endpoint()).unwrap();
+
+        assert!(client.get_approvals().await.unwrap().iter().all(|a| !a.is_approved()));
+        assert_eq!(client.set_approvals().await.unwrap().len(), 7);
+        assert!(client.get_approvals().await.unwrap().iter().all(|a| a.is_approved()));
+        assert!(client.set_approvals().await.unwrap().is_empty());
+    }
+}
@@ -0,0 +1,193 @@
+use std::{fs, path::PathBuf};
+use aes_gcm::{aead::{Aead, KeyInit}, Aes256Gcm, Nonce};
+use alloy::primitives::{hex, keccak256, U256};
+use rand::RngCore;
+use zeroize::Zeroizing;
+use crate::{signer::ClobSigner, Error, Result};
+use super::ApiCreds;
+
+// Signed to derive the encryption key, ECDSA signatures are deterministic (RFC 6979)
+// so only the same signer can reproduce the key. Remote signers must sign deterministically too.
+const CACHE_KEY_MSG: &str = "This message derives the key for the clob-rs API credentials cache";
+const NONCE_LEN: usize = 12;
+
+// Encrypted on-disk store of API credentials, one file per signer address, chain id, host and nonce.
+#[derive(Debug, Clone)]
+pub struct CredsCache {
+    dir: PathBuf,
+}
+
+impl CredsCache {
+
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    // File name up to the nonce, the host is hashed as URLs aren't valid file names.
+    fn prefix(signer: &dyn ClobSigner, host: &str) -> String {
+        let chain_id = signer.chain_id().unwrap_or(137);
+        let host = hex::encode(&keccak256(host.trim_end_matches('/'))[..8]);
+        format!("{}-{}-{}-", signer.address(), chain_id, host)
+    }
+
+    fn path(&self, signer: &dyn ClobSigner, host: &str, nonce: U256) -> PathBuf {
+        self.dir.join(format!("{}{}.creds", Self::prefix(signer, host), nonce))
+    }
+
+    // Returns none if nothing has been cached for the signer at the host and nonce.
+    pub async fn load(&self, signer: &dyn ClobSigner, host: &str, nonce: U256) -> Result<Option<ApiCreds>> {
+        let path = self.path(signer, host, nonce);
+        if !path.exists() {
+            return Ok(None);
+        }
+
+        Ok(Some(decrypt(&cipher(signer).await?, &fs::read(path)?)?))
+    }
+
+    pub async fn store(&self, signer: &dyn ClobSigner, host: &str, nonce: U256, creds: &ApiCreds) -> Result<()> {
+        let mut iv = [0u8; NONCE_LEN];
+        rand::thread_rng().fill_bytes(&mut iv);
+
+        let plaintext = Zeroizing::new(serde_json::to_vec(creds)?);
+        let ciphertext = cipher(signer).await?
+            .encrypt(Nonce::from_slice(&iv), plaintext.as_slice())
+            .map_err(|_| Error::CredsCacheEncryptFailed)?;
+
+        fs::create_dir_all(&self.dir)?;
+        fs::write(self.path(signer, host, nonce), [iv.as_slice(), &ciphertext].concat())?;
+        Ok(())
+    }
+
+    pub fn remove(&self, signer: &dyn ClobSigner, host: &str, nonce: U256) -> Result<()> {
+        let path = self.path(signer, host, nonce);
+        if path.exists() {
+            fs::remove_file(path)?;
+        }
+        Ok(())
+    }
+
+    // Removes the signer's entries at the host holding the api key, whatever nonce they were cached under.
+    // Entries that can't be decrypted are left alone.
+    pub async fn remove_api_key(&self, signer: &dyn ClobSigner, host: &str, api_key: &str) -> Result<()> {
+        if !self.dir.exists() {
+            return Ok(());
+        }
+
+        let prefix = Self::prefix(signer, host);
+        let cipher = cipher(signer).await?;
+        for entry in fs::read_dir(&self.dir)? {
+            let path = entry?.path();
+            let is_entry = path.file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| name.starts_with(&prefix) && name.ends_with(".creds"));
+            if !is_entry {
+                continue;
+            }
+            if let Ok(creds) = decrypt(&cipher, &fs::read(&path)?) {
+                if creds.api_key == api_key {
+                    fs::remove_file(path)?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+fn decrypt(cipher: &Aes256Gcm, data: &[u8]) -> Result<ApiCreds> {
+    if data.len() < NONCE_LEN {
+        return Err(Error::CredsCacheDecryptFailed);
+    }
+    let (iv, ciphertext) = data.split_at(NONCE_LEN);
+    let plaintext = Zeroizing::new(
+        cipher.decrypt(Nonce::from_slice(iv), ciphertext)
+            .map_err(|_| Error::CredsCacheDecryptFailed)?
+    );
+    Ok(serde_json::from_slice(&plaintext)?)
+}
+
+async fn cipher(signer: &dyn ClobSigner) -> Result<Aes256Gcm> {
+    let sig = signer.sign_message(CACHE_KEY_MSG.as_bytes()).await?;
+    let key = Zeroizing::new(keccak256(sig.as_bytes()).0);
+    // Cannot fail, key is always 32 bytes.
+    Ok(Aes256Gcm::new_from_slice(key.as_ref()).unwrap())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+    use alloy::signers::{local::PrivateKeySigner, Signer};
+    use super::*;
+
+    const PRIVATE_KEY: &str = "0xac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80";
+    const OTHER_KEY: &str = "0x59c6995e998f97a5a0044966f0945389dc9e86dae88c7a8412f4603b6b78690d";
+    const HOST: &str = "https://clob.polymarket.com";
+
+    fn get_signer(key: &str) -> PrivateKeySigner {
+        PrivateKeySigner::from_str(key).unwrap().with_chain_id(Some(80002))
+    }
+
+    fn get_creds() -> ApiCreds {
+        ApiCreds {
+            api_key:    "00000000-0000-0000-0000-000000000000".to_string(),
+            secret:     "AAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAA=".to_string(),
+            passphrase: "passphrase".to_string(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_creds_cache_round_trip() {
+        let dir = std::env::temp_dir().join(format!("clob-creds-{}", rand::random::<u64>()));
+        let cache = CredsCache::new(&dir);
+        let signer = get_signer(PRIVATE_KEY);
+
+        assert!(cache.load(&signer, HOST, U256::ZERO).await.unwrap().is_none());
+        cache.store(&signer, HOST, U256::ZERO, &get_creds()).await.unwrap();
+
+        let creds = cache.load(&signer, HOST, U256::ZERO).await.unwrap().unwrap();
+        assert_eq!(creds.api_key, get_creds().api_key);
+        assert_eq!(creds.secret, get_creds().secret);
+        assert_eq!(creds.passphrase, get_creds().passphrase);
+
+        // Different chain id, host or nonce is a different entry.
+        assert!(cache.load(&signer.clone().with_chain_id(Some(137)), HOST, U256::ZERO).await.unwrap().is_none());
+        assert!(cache.load(&signer, "http://localhost:8080", U256::ZERO).await.unwrap().is_none());
+        assert!(cache.load(&signer, HOST, U256::from(1)).await.unwrap().is_none());
+        // Same host with a trailing slash.
+        assert!(cache.load(&signer, &format!("{HOST}/"), U256::ZERO).await.unwrap().is_some());
+
+        cache.remove(&signer, HOST, U256::ZERO).unwrap();
+        assert!(cache.load(&signer, HOST, U256::ZERO).await.unwrap().is_none());
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_creds_cache_wrong_signer() {
+        let dir = std::env::temp_dir().join(format!("clob-creds-{}", rand::random::<u64>()));
+        let cache = CredsCache::new(&dir);
+        let signer = get_signer(PRIVATE_KEY);
+        cache.store(&signer, HOST, U256::ZERO, &get_creds()).await.unwrap();
+
+        // Copy the entry to where the other signer would look for it.
+        let other = get_signer(OTHER_KEY);
+        fs::copy(cache.path(&signer, HOST, U256::ZERO), cache.path(&other, HOST, U256::ZERO)).unwrap();
+        assert!(matches!(cache.load(&other, HOST, U256::ZERO).await, Err(Error::CredsCacheDecryptFailed)));
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_creds_cache_remove_api_key() {
+        let dir = std::env::temp_dir().join(format!("clob-creds-{}", rand::random::<u64>()));
+        let cache = CredsCache::new(&dir);
+        let signer = get_signer(PRIVATE_KEY);
+        let mut other_creds = get_creds();
+        other_creds.api_key = "11111111-1111-1111-1111-111111111111".to_string();
+        cache.store(&signer, HOST, U256::from(3), &get_creds()).await.unwrap();
+        cache.store(&signer, HOST, U256::ZERO, &other_creds).await.unwrap();
+        fs::write(dir.join("corrupt.creds"), b"corrupt").unwrap();
+
+        cache.remove_api_key(&signer, HOST, &get_creds().api_key).await.unwrap();
+        assert!(cache.load(&signer, HOST, U256::from(3)).await.unwrap().is_none());
+        assert!(cache.load(&signer, HOST, U256::ZERO).await.unwrap().is_some());
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,163 @@
+use alloy::{
+    network::TransactionBuilder,
+    primitives::{Address, Bytes, TxHash, B256, U256},
+    providers::Provider,
+    rpc::types::TransactionRequest,
+    sol_types::SolCall,
+};
+use crate::{abi::{IConditionalTokens, INegRiskAdapter}, contracts::ContractConfig, schema::ConditionId, Result};
+use super::{confirm_tx, ClobClient};
+
+// Positions in Polymarket markets are top level, they have no parent collection.
+const PARENT_COLLECTION_ID: B256 = B256::ZERO;
+
+// Index sets of the two outcomes of a binary condition.
+fn binary_partition() -> Vec<U256> {
+    vec![U256::from(1), U256::from(2)]
+}
+
+// Target and calldata of each call, neg risk markets go through the neg risk adapter.
+
+fn split_call(contracts: &ContractConfig, condition_id: B256, amount: U256, neg_risk: bool) -> (Address, Bytes) {
+    if neg_risk {
+        let call = INegRiskAdapter::splitPositionCall { conditionId: condition_id, amount };
+        (contracts.neg_risk_adapter, call.abi_encode().into())
+    } else {
+        let call = IConditionalTokens::splitPositionCall {
+            collateralToken:    contracts.collateral,
+            parentCollectionId: PARENT_COLLECTION_ID,
+            conditionId:        condition_id,
+            partition:          binary_partition(),
+            amount,
+        };
+        (contracts.conditional_tokens, call.abi_encode().into())
+    }
+}
+
+fn merge_call(contracts: &ContractConfig, condition_id: B256, amount: U256, neg_risk: bool) -> (Address, Bytes) {
+    if neg_risk {
+        let call = INegRiskAdapter::mergePositionsCall { conditionId: condition_id, amount };
+        (contracts.neg_risk_adapter, call.abi_encode().into())
+    } else {
+        let call = IConditionalTokens::mergePositionsCall {
+            collateralToken:    contracts.collateral,
+            parentCollectionId: PARENT_COLLECTION_ID,
+            conditionId:        condition_id,
+            partition:          binary_partition(),
+            amount,
+        };
+        (contracts.conditional_tokens, call.abi_encode().into())
+    }
+}
+
+fn redeem_call(contracts: &ContractConfig, condition_id: B256) -> (Address, Bytes) {
+    let call = IConditionalTokens::redeemPositionsCall {
+        collateralToken:    contracts.collateral,
+        parentCollectionId: PARENT_COLLECTION_ID,
+        conditionId:        condition_id,
+        indexSets:          binary_partition(),
+    };
+    (contracts.conditional_tokens, call.abi_encode().into())
+}
+
+fn redeem_neg_risk_call(contracts: &ContractConfig, condition_id: B256, amounts: [U256; 2]) -> (Address, Bytes) {
+    let call = INegRiskAdapter::redeemPositionsCall { conditionId: condition_id, amounts: amounts.to_vec() };
+    (contracts.neg_risk_adapter, call.abi_encode().into())
+}
+
+// Amounts are in collateral base units (USDC has 6 decimals), one unit makes one of each outcome token.
+// Splitting pulls collateral, so the conditional tokens contract or the neg risk adapter must be approved, see `set_approvals`.
+impl ClobClient {
+
+    // Mint a complete set of outcome tokens from collateral.
+    pub async fn split_position(&self, condition_id: ConditionId, amount: U256, neg_risk: bool) -> Result<TxHash> {
+        self.send_call(split_call(self.get_contracts()?, condition_id.0, amount, neg_risk)).await
+    }
+
+    // Burn a complete set of outcome tokens back into collateral.
+    pub async fn merge_positions(&self, condition_id: ConditionId, amount: U256, neg_risk: bool) -> Result<TxHash> {
+        self.send_call(merge_call(self.get_contracts()?, condition_id.0, amount, neg_risk)).await
+    }
+
+    // Redeem all held outcome tokens of a resolved condition for collateral.
+    pub async fn redeem_positions(&self, condition_id: ConditionId) -> Result<TxHash> {
+        self.send_call(redeem_call(self.get_contracts()?, condition_id.0)).await
+    }
+
+    // Redeem outcome tokens of a resolved neg risk condition, amounts are of the yes and no tokens.
+    pub async fn redeem_neg_risk_positions(&self, condition_id: ConditionId, amounts: [U256; 2]) -> Result<TxHash> {
+        self.send_call(redeem_neg_risk_call(self.get_contracts()?, condition_id.0, amounts)).await
+    }
+
+    async fn send_call(&self, (to, input): (Address, Bytes)) -> Result<TxHash> {
+        self.require_eoa()?;
+        let provider = self.get_provider()?;
+        let pending = provider.send_transaction(TransactionRequest::default().with_to(to).with_input(input)).await?;
+        confirm_tx(pending).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy::primitives::hex;
+    use crate::contracts::MATIC_CONTRACTS;
+    use super::*;
+
+    fn get_condition_id() -> B256 {
+        B256::repeat_byte(0xab)
+    }
+
+    // 32 byte ABI words of the calldata after the selector.
+    fn words(data: &[u8]) -> Vec<U256> {
+        data[4..].chunks(32).map(U256::from_be_slice).collect()
+    }
+
+    #[test]
+    fn test_split_merge_calls() {
+        let amount = U256::from(1_000_000);
+        for (call, selector) in [(split_call as fn(_, _, _, _) -> _, "72ce4275"), (merge_call, "9e7212ad")] {
+            let (to, data) = call(&MATIC_CONTRACTS, get_condition_id(), amount, false);
+            assert_eq!(to, MATIC_CONTRACTS.conditional_tokens);
+            assert_eq!(hex::encode(&data[..4]), selector);
+            // Collateral, parent collection, condition, offset of the partition, amount, then the partition [1, 2].
+            assert_eq!(words(&data), vec![
+                U256::from_be_slice(MATIC_CONTRACTS.collateral.as_slice()),
+                U256::ZERO,
+                U256::from_be_bytes(get_condition_id().0),
+                U256::from(5 * 32),
+                amount,
+                U256::from(2),
+                U256::from(1),
+                U256::from(2),
+            ]);
+
+            // The adapter takes the condition and amount only.
+            let (to, data) = call(&MATIC_CONTRACTS, get_condition_id(), amount, true);
+            assert_eq!(to, MATIC_CONTRACTS.neg_risk_adapter);
+            assert_eq!(words(&data), vec![U256::from_be_bytes(get_condition_id().0), amount]);
+        }
+    }
+
+    #[test]
+    fn test_redeem_calls() {
+        let (to, data) = redeem_call(&MATIC_CONTRACTS, get_condition_id());
+        assert_eq!(to, MATIC_CONTRACTS.conditional_tokens);
+        assert_eq!(hex::encode(&data[..4]), "01b7037c");
+        // Collateral, parent collection, condition, offset of the index sets, then the index sets [1, 2].
+        assert_eq!(words(&data), vec![
+            U256::from_be_slice(MATIC_CONTRACTS.collateral.as_slice()),
+            U256::ZERO,
+            U256::from_be_bytes(get_condition_id().0),
+            U256::from(4 * 32),
+            U256::from(2),
+            U256::from(1),
+            U256::from(2),
+        ]);
+
+        let amounts = [U256::from(5), U256::from(7)];
+        let (to, data) = redeem_neg_risk_call(&MATIC_CONTRACTS, get_condition_id(), amounts);
+        assert_eq!(to, MATIC_CONTRACTS.neg_risk_adapter);
+        // Condition, offset of the amounts, then the yes and no amounts.
+        assert_eq!(words(&data), vec![U256::from_be_bytes(get_condition_id().0), U256::from(2 * 32), U256::from(2), amounts[0], amounts[1]]);
+    }
+}
@@ -0,0 +1,140 @@
+use std::str::FromStr;
+use alloy::primitives::{Address, Bytes, TxHash, U256};
+use serde::{Deserialize, Serialize};
+use crate::{abi::ICTFExchange, indexer::FillIndexer, schema::SignedOrder, Error, Result};
+use super::{confirm_tx, ClobClient};
+
+// Exchange nonces of the maker, orders are only valid with the current nonce of the exchange they are for.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Nonces {
+    pub exchange:          U256,
+    pub neg_risk_exchange: U256,
+}
+
+impl Nonces {
+    pub fn get(&self, neg_risk: bool) -> U256 {
+        if neg_risk { self.neg_risk_exchange } else { self.exchange }
+    }
+}
+
+fn to_exchange_order(signed_order: &SignedOrder) -> Result<ICTFExchange::Order> {
+    let order = &signed_order.order;
+    Ok(ICTFExchange::Order {
+        salt:          order.salt,
+        maker:         order.maker,
+        signer:        order.signer,
+        taker:         order.taker,
+        tokenId:       order.tokenId,
+        makerAmount:   order.makerAmount,
+        takerAmount:   order.takerAmount,
+        expiration:    order.expiration,
+        nonce:         order.nonce,
+        feeRateBps:    order.feeRateBps,
+        side:          order.side,
+        signatureType: order.signatureType,
+        signature:     Bytes::from_str(&signed_order.signature).map_err(|_| Error::InvalidOrderSignature)?,
+    })
+}
+
+// On-chain fallbacks for when the CLOB API is unreachable, these only work for orders made by an EOA.
+impl ClobClient {
+
+    fn get_exchange(&self, neg_risk: bool) -> Result<Address> {
+        let contracts = self.get_contracts()?;
+        Ok(if neg_risk { contracts.neg_risk_exchange } else { contracts.exchange })
+    }
+
+    pub async fn cancel_order_onchain(&self, order: &SignedOrder, neg_risk: bool) -> Result<TxHash> {
+        self.require_eoa()?;
+        let provider = self.get_provider()?;
+        let pending = ICTFExchange::new(self.get_exchange(neg_risk)?, &provider)
+            .cancelOrder(to_exchange_order(order)?)
+            .send()
+            .await?;
+        confirm_tx(pending).await
+    }
+
+    // All orders must be for the same exchange.
+    pub async fn cancel_orders_onchain(&self, orders: &[SignedOrder], neg_risk: bool) -> Result<TxHash> {
+        self.require_eoa()?;
+        let provider = self.get_provider()?;
+        let orders = orders.iter().map(to_exchange_order).collect::<Result<Vec<_>>>()?;
+        let pending = ICTFExchange::new(self.get_exchange(neg_risk)?, &provider)
+            .cancelOrders(orders)
+            .send()
+            .await?;
+        confirm_tx(pending).await
+    }
+
+    // Invalidates every order signed with the current nonce on the exchange,
+    // then updates the tracked nonce so new orders are valid.
+    pub async fn increment_nonce(&self, neg_risk: bool) -> Result<TxHash> {
+        self.require_eoa()?;
+        let provider = self.get_provider()?;
+        let pending = ICTFExchange::new(self.get_exchange(neg_risk)?, &provider)
+            .incrementNonce()
+            .send()
+            .await?;
+        let tx_hash = confirm_tx(pending).await?;
+        self.sync_nonces().await?;
+        Ok(tx_hash)
+    }
+
+    // Reads the maker's current nonces from both exchanges and tracks them for new orders.
+    pub async fn sync_nonces(&self) -> Result<Nonces> {
+        let provider = self.get_provider()?;
+        let maker = self.get_maker_address()?;
+        let nonces = Nonces {
+            exchange:          ICTFExchange::new(self.get_exchange(false)?, &provider).nonces(maker).call().await?._0,
+            neg_risk_exchange: ICTFExchange::new(self.get_exchange(true)?, &provider).nonces(maker).call().await?._0,
+        };
+        *self.nonces.write().unwrap_or_else(|e| e.into_inner()) = nonces;
+        Ok(nonces)
+    }
+
+    // Nonces used for new orders.
+    pub fn get_nonces(&self) -> Nonces {
+        *self.nonces.read().unwrap_or_else(|e| e.into_inner())
+    }
+
+    // Indexer over the configured rpc, filtered to fills of the maker address.
+    pub fn fill_indexer(&self) -> Result<FillIndexer> {
+        let rpc_url = self.rpc_url.as_ref().ok_or(Error::RpcRequired)?;
+        Ok(FillIndexer::new(rpc_url.as_str(), self.get_contracts()?)?.with_maker(self.get_maker_address()?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy::primitives::Address;
+    use crate::schema::Order;
+    use super::*;
+
+    #[test]
+    fn test_to_exchange_order() {
+        let signed_order = SignedOrder {
+            order: Order {
+                salt:           U256::from(1),
+                maker:          Address::with_last_byte(1),
+                signer:         Address::with_last_byte(1),
+                taker:          Address::ZERO,
+                tokenId:        U256::from(1234),
+                makerAmount:    U256::from(100),
+                takerAmount:    U256::from(200),
+                expiration:     U256::ZERO,
+                nonce:          U256::from(3),
+                feeRateBps:     U256::ZERO,
+                side:           1,
+                signatureType:  0,
+            },
+            signature: format!("0x{}", "ab".repeat(65)),
+        };
+        let order = to_exchange_order(&signed_order).unwrap();
+        assert_eq!(order.nonce, U256::from(3));
+        assert_eq!(order.side, 1);
+        assert_eq!(order.signature.len(), 65);
+
+        let invalid = SignedOrder { signature: "0xzz".to_string(), ..signed_order };
+        assert!(matches!(to_exchange_order(&invalid), Err(Error::InvalidOrderSignature)));
+    }
+}
@@ -19,7 +19,7 @@ impl ClobClient {
         self.request(RequestArgs {
             method: Method::GET,
             path: "/markets",
-            queries: Some(&[("next_cursor", next_cursor.unwrap_or("MTA="))]),
+            queries: Some(&[("next_cursor", next_cursor.unwrap_or(START_CURSOR))]),
             body: None,
             auth_level: AuthLevel::None,
         }).await
@@ -41,7 +41,7 @@ impl ClobClient {
         self.request(RequestArgs {
             method: Method::GET,
             path: "/simplified-markets",
-            queries: Some(&[("next_cursor", next_cursor.unwrap_or("MTA="))]),
+            queries: Some(&[("next_cursor", next_cursor.unwrap_or(START_CURSOR))]),
             body: None,
             auth_level: AuthLevel::None,
         }).await
@@ -51,7 +51,7 @@ impl ClobClient {
         self.request(RequestArgs {
             method: Method::GET,
             path: "/sampling-simplified-markets",
-            queries: Some(&[("next_cursor", next_cursor.unwrap_or("MTA="))]),
+            queries: Some(&[("next_cursor", next_cursor.unwrap_or(START_CURSOR))]),
             body: None,
             auth_level: AuthLevel::None,
         }).await
@@ -77,6 +77,21 @@ impl ClobClient {
         )
     }
 
+    // Base fee rate in bps that orders of the token must be signed with.
+    pub async fn get_fee_rate(&self, token_id: &str) -> Result<u32> {
+        Ok(
+            self.request::<FeeRate>(RequestArgs {
+                method: Method::GET,
+                path: "/fee-rate",
+                queries: Some(&[("token_id", token_id)]),
+                body: None,
+                auth_level: AuthLevel::None,
+            })
+            .await?
+            .base_fee
+        )
+    }
+
     pub async fn is_neg_risk(&self, token_id: &str) -> Result<bool> {
 
         #[derive(Deserialize, Debug)]
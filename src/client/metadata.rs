@@ -0,0 +1,180 @@
+use std::{collections::HashMap, sync::{Arc, RwLock}, time::Duration};
+use chrono::{DateTime, Utc};
+use crate::{schema::{Market, TokenId}, Error, Result};
+use super::ClobClient;
+
+// How long market metadata is reused before being fetched again.
+pub const DEFAULT_METADATA_TTL: Duration = Duration::from_secs(300);
+
+// What order creation needs to know about a token's market.
+#[derive(Debug, Clone)]
+pub struct MarketMetadata {
+    pub neg_risk:       bool,
+    // Minimum tick size in units of implied probability.
+    pub tick_size:      f64,
+    // Base fee rates in bps.
+    pub maker_base_fee: u32,
+    pub taker_base_fee: u32,
+    pub market:         Arc<Market>,
+}
+
+impl MarketMetadata {
+    // Prices are in thousandths as with `OrderArgs`, valid prices are multiples of the tick size.
+    // Ticks finer than a thousandth allow any price strictly between 0 and 1.
+    pub fn check_price(&self, price: u32) -> Result<()> {
+        let tick = ((self.tick_size * 1000.0).round() as u32).max(1);
+        if !price.is_multiple_of(tick) || price < tick || price > 1000 - tick {
+            return Err(Error::InvalidTickSize(self.tick_size));
+        }
+        Ok(())
+    }
+
+    // Fee rate orders are signed with, the exchange charges it to whichever side takes.
+    pub fn fee_rate_bps(&self) -> u32 {
+        self.taker_base_fee
+    }
+}
+
+#[derive(Debug)]
+struct Entry {
+    metadata:   MarketMetadata,
+    fetched_at: DateTime<Utc>,
+}
+
+// Market metadata keyed by token id, shared between clones of a client.
+#[derive(Debug)]
+pub(crate) struct MetadataCache {
+    ttl:     Duration,
+    entries: RwLock<HashMap<TokenId, Entry>>,
+}
+
+impl MetadataCache {
+
+    pub fn new(ttl: Duration) -> Self {
+        Self { ttl, entries: Default::default() }
+    }
+
+    // None if missing or expired.
+    pub fn get(&self, token_id: TokenId, now: DateTime<Utc>) -> Option<MarketMetadata> {
+        let entries = self.entries.read().unwrap_or_else(|e| e.into_inner());
+        let entry = entries.get(&token_id)?;
+        let age = (now - entry.fetched_at).to_std().unwrap_or_default();
+        (age <= self.ttl).then(|| entry.metadata.clone())
+    }
+
+    // Caches every token of the market.
+    pub fn insert(&self, market: Market, now: DateTime<Utc>) {
+        let market = Arc::new(market);
+        let mut entries = self.entries.write().unwrap_or_else(|e| e.into_inner());
+        for token in &market.tokens {
+            let metadata = MarketMetadata {
+                neg_risk:       market.neg_risk,
+                tick_size:      market.minimum_tick_size,
+                maker_base_fee: market.maker_base_fee,
+                taker_base_fee: market.taker_base_fee,
+                market:         market.clone(),
+            };
+            entries.insert(token.token_id, Entry { metadata, fetched_at: now });
+        }
+    }
+
+    pub fn set_tick_size(&self, token_id: TokenId, tick_size: f64) {
+        let mut entries = self.entries.write().unwrap_or_else(|e| e.into_inner());
+        if let Some(entry) = entries.get_mut(&token_id) {
+            entry.metadata.tick_size = tick_size;
+        }
+    }
+
+    pub fn remove(&self, token_id: TokenId) {
+        self.entries.write().unwrap_or_else(|e| e.into_inner()).remove(&token_id);
+    }
+}
+
+impl ClobClient {
+
+    // Cached metadata of the token's market, fetched if missing or expired.
+    pub async fn get_market_metadata(&self, token_id: TokenId) -> Result<MarketMetadata> {
+        if let Some(metadata) = self.metadata.get(token_id, self.clock.now()) {
+            return Ok(metadata);
+        }
+
+        // The book is the only token keyed endpoint that names the market.
+        let book = self.get_market_book(&token_id.to_string()).await?;
+        let market = self.get_market(&book.market.to_string()).await?;
+        self.cache_market(market);
+        self.metadata
+            .get(token_id, self.clock.now())
+            .ok_or(Error::TokenNotInMarket)
+    }
+
+    // Cached metadata without fetching, none if missing or expired.
+    pub fn get_cached_market_metadata(&self, token_id: TokenId) -> Option<MarketMetadata> {
+        self.metadata.get(token_id, self.clock.now())
+    }
+
+    // Caches the metadata of all tokens of an already fetched market.
+    pub fn cache_market(&self, market: Market) {
+        self.metadata.insert(market, self.clock.now());
+    }
+
+    // Applies a tick size change, e.g. from a market channel `tick_size_change` event.
+    pub fn set_tick_size(&self, token_id: TokenId, tick_size: f64) {
+        self.metadata.set_tick_size(token_id, tick_size);
+    }
+
+    pub fn invalidate_market_metadata(&self, token_id: TokenId) {
+        self.metadata.remove(token_id);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy::primitives::U256;
+    use crate::schema::get_market;
+    use super::*;
+
+    #[test]
+    fn test_metadata_cache_ttl() {
+        let cache = MetadataCache::new(Duration::from_secs(60));
+        let now = DateTime::from_timestamp(1_000_000, 0).unwrap();
+        let yes = TokenId(U256::from(1));
+        let no = TokenId(U256::from(2));
+        cache.insert(get_market(), now);
+
+        let metadata = cache.get(no, now).unwrap();
+        assert!(metadata.neg_risk);
+        assert_eq!(metadata.taker_base_fee, 200);
+        assert!(cache.get(yes, now + chrono::Duration::seconds(60)).is_some());
+        assert!(cache.get(yes, now + chrono::Duration::seconds(61)).is_none());
+        assert!(cache.get(TokenId(U256::from(3)), now).is_none());
+
+        cache.set_tick_size(yes, 0.001);
+        assert_eq!(cache.get(yes, now).unwrap().tick_size, 0.001);
+        assert_eq!(cache.get(no, now).unwrap().tick_size, 0.01);
+
+        cache.remove(yes);
+        assert!(cache.get(yes, now).is_none());
+    }
+
+    #[test]
+    fn test_check_price() {
+        let cache = MetadataCache::new(DEFAULT_METADATA_TTL);
+        let now = DateTime::from_timestamp(1_000_000, 0).unwrap();
+        cache.insert(get_market(), now);
+        let metadata = cache.get(TokenId(U256::from(1)), now).unwrap();
+
+        assert!(metadata.check_price(500).is_ok());
+        assert!(metadata.check_price(990).is_ok());
+        assert!(matches!(metadata.check_price(505), Err(Error::InvalidTickSize(_))));
+        assert!(metadata.check_price(0).is_err());
+        assert!(metadata.check_price(1000).is_err());
+
+        cache.set_tick_size(TokenId(U256::from(1)), 0.0001);
+        let metadata = cache.get(TokenId(U256::from(1)), now).unwrap();
+        assert!(metadata.check_price(1).is_ok());
+        assert!(metadata.check_price(505).is_ok());
+        assert!(metadata.check_price(999).is_ok());
+        assert!(metadata.check_price(0).is_err());
+        assert!(metadata.check_price(1000).is_err());
+    }
+}
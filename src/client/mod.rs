@@ -1,15 +1,45 @@
-use std::{str::FromStr, env::var};
-use alloy::signers::{local::PrivateKeySigner, Signer};
+use std::{fmt, str::FromStr, env::var, path::Path, sync::{Arc, RwLock}, time::Duration};
+use alloy::{
+    network::{Ethereum, EthereumWallet},
+    primitives::TxHash,
+    providers::{PendingTransactionBuilder, Provider, ProviderBuilder},
+    signers::local::PrivateKeySigner,
+    transports::http::{Client, Http},
+};
 use alloy::primitives::{Address, U256};
-use crate::{auth, contracts::SUPPORTED_CHAIN_IDS, Error, Result};
+use reqwest::header::HeaderMap;
+use zeroize::{Zeroize, ZeroizeOnDrop, Zeroizing};
+use crate::{
+    auth,
+    clock::{Clock, OffsetClock, SystemClock},
+    contracts::{get_contracts, register_contracts_from_file, ContractConfig},
+    salt::{RandomSalt, SaltGenerator},
+    signer::{self, ClobSigner, RemoteSigner, TxSignerAdapter},
+    wallet,
+    Error,
+    Result,
+};
 
+mod approvals;
+mod creds;
+mod ctf;
+mod exchange;
 mod markets;
+mod metadata;
 mod orders;
+mod rewards;
+mod time;
 mod user;
 
+pub use approvals::Approval;
+pub use creds::CredsCache;
+pub use exchange::Nonces;
+pub use metadata::{MarketMetadata, DEFAULT_METADATA_TTL};
 pub use orders::*;
+use metadata::MetadataCache;
 
-#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+// Redacted from debug output and wiped on drop, display shows the api key only.
+#[derive(Clone, serde::Serialize, serde::Deserialize, Zeroize, ZeroizeOnDrop)]
 pub struct ApiCreds {
     #[serde(rename = "apiKey")]
     pub api_key:    String,
@@ -27,6 +57,22 @@ impl ApiCreds {
     }
 }
 
+impl fmt::Debug for ApiCreds {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ApiCreds")
+            .field("api_key", &"<redacted>")
+            .field("secret", &"<redacted>")
+            .field("passphrase", &"<redacted>")
+            .finish()
+    }
+}
+
+impl fmt::Display for ApiCreds {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} (secret and passphrase redacted)", self.api_key)
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct RequestArgs<'a> {
     pub method:     reqwest::Method,
@@ -47,18 +93,56 @@ pub enum AuthLevel {
     L2,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Clone)]
 pub struct ClobClient {
     // HTTP client.
-    client:   reqwest::Client,
+    client:       reqwest::Client,
     // Base HTTP url.
-    base_url: String,
-    // Chain signer.
-    signer:   Option<PrivateKeySigner>,
+    base_url:     String,
+    // Chain signer, local key material is zeroized on drop by k256.
+    signer:       Option<Arc<dyn ClobSigner>>,
     // Polymarket API credentials.
-    creds:    Option<ApiCreds>,
+    creds:        Option<ApiCreds>,
     // If none sig type is EOA.
-    proxy:    Option<Proxy>,
+    proxy:        Option<Proxy>,
+    // Where derived or created creds are persisted, if set.
+    creds_cache:  Option<CredsCache>,
+    // Time source for auth header timestamps.
+    clock:        Arc<dyn Clock>,
+    // Same as clock when corrected to server time.
+    server_clock: Option<Arc<OffsetClock>>,
+    // Salt source for signed orders.
+    salt:         Arc<dyn SaltGenerator>,
+    // Deployments of the signer's chain.
+    contracts:    Option<ContractConfig>,
+    // Chain the contracts are for, none if set with `with_contracts` before any signer.
+    chain_id:     Option<u64>,
+    // Exchange nonces signed into new orders, shared between clones.
+    nonces:       Arc<RwLock<Nonces>>,
+    // Polygon JSON-RPC endpoint for on-chain calls.
+    rpc_url:      Option<reqwest::Url>,
+    // Market metadata by token id, shared between clones.
+    metadata:     Arc<MetadataCache>,
+}
+
+// Only shows the signer address, never key material or creds secrets.
+impl fmt::Debug for ClobClient {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ClobClient")
+            .field("base_url", &self.base_url)
+            .field("signer", &self.signer.as_ref().map(|s| s.address()))
+            .field("creds", &self.creds)
+            .field("proxy", &self.proxy)
+            .field("creds_cache", &self.creds_cache)
+            .field("clock", &self.clock)
+            .field("salt", &self.salt)
+            .field("contracts", &self.contracts)
+            .field("chain_id", &self.chain_id)
+            .field("nonces", &self.get_nonces())
+            .field("rpc_url", &self.rpc_url)
+            .field("metadata", &self.metadata)
+            .finish()
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -71,32 +155,126 @@ impl ClobClient {
 
     pub fn new(base_url: &str) -> Self {
         Self { 
-            client:   reqwest::Client::new(),
-            base_url: base_url.to_string(),
-            signer:   None,
-            creds:    None,
-            proxy:    None,
+            client:       reqwest::Client::new(),
+            base_url:     base_url.to_string(),
+            signer:       None,
+            creds:        None,
+            proxy:        None,
+            creds_cache:  None,
+            clock:        Arc::new(SystemClock),
+            server_clock: None,
+            salt:         Arc::new(RandomSalt),
+            contracts:    None,
+            chain_id:     None,
+            nonces:       Default::default(),
+            rpc_url:      None,
+            metadata:     Arc::new(MetadataCache::new(DEFAULT_METADATA_TTL)),
         }
     }
 
-    pub fn with_signer(mut self, private_key: &str, chain_id: u64) -> Result<Self> {
-        if !SUPPORTED_CHAIN_IDS.contains(&chain_id) {
-            return Err(Error::InvalidChainId);
+    pub fn with_signer(self, private_key: &str, chain_id: u64) -> Result<Self> {
+        let signer = PrivateKeySigner::from_str(private_key).map_err(|_| Error::InvalidPrivateKey)?;
+        self.with_custom_signer(signer, chain_id)
+    }
+
+    // Signer from an encrypted JSON keystore file.
+    pub fn with_keystore(self, path: impl AsRef<Path>, password: &str, chain_id: u64) -> Result<Self> {
+        self.with_custom_signer(signer::keystore_signer(path, password)?, chain_id)
+    }
+
+    // Signer from a BIP-39 mnemonic, derivation path defaults to the first account.
+    pub fn with_mnemonic(self, phrase: &str, derivation_path: Option<&str>, chain_id: u64) -> Result<Self> {
+        self.with_custom_signer(signer::mnemonic_signer(phrase, derivation_path)?, chain_id)
+    }
+
+    // Signer backed by a remote HTTP signing service, see `RemoteSigner`.
+    pub fn with_remote_signer(self, url: &str, address: &str, chain_id: u64) -> Result<Self> {
+        self.with_custom_signer(RemoteSigner::new(url, address)?, chain_id)
+    }
+
+    // Contracts are looked up for the chain unless already set for it with `with_contracts`,
+    // a signer on another chain replaces them with that chain's.
+    pub fn with_custom_signer(mut self, signer: impl ClobSigner + 'static, chain_id: u64) -> Result<Self> {
+        if self.contracts.is_none() || self.chain_id.is_some_and(|chain| chain != chain_id) {
+            self.contracts = Some(get_contracts(chain_id)?);
         }
-        self.signer = Some(
-            PrivateKeySigner::from_str(private_key)
-                .map_err(|_| Error::InvalidPrivateKey)?
-                .with_chain_id(Some(chain_id))
-        );
+        self.chain_id = Some(chain_id);
+        self.signer = Some(Arc::new(signer.with_chain_id(Some(chain_id))));
         Ok(self)
     }
 
+    // Nonces for new orders, `sync_nonces` reads them from chain instead.
+    pub fn with_nonces(self, nonces: Nonces) -> Self {
+        *self.nonces.write().unwrap_or_else(|e| e.into_inner()) = nonces;
+        self
+    }
+
+    // Use custom deployments, e.g. on a local devnet. Set before the signer for chains
+    // without built in or registered contracts.
+    pub fn with_contracts(mut self, contracts: ContractConfig) -> Self {
+        self.contracts = Some(contracts);
+        self.chain_id = self.signer.as_ref().map(|signer| signer.chain_id().unwrap_or(137));
+        self
+    }
+
     pub fn with_creds(mut self, creds: ApiCreds) -> Self {
         self.creds = Some(creds);
         self
     }
 
-    pub fn with_proxy(mut self, address: &str, sig_type: SignatureType) -> Result<Self> {
+    pub fn with_creds_cache(mut self, cache: CredsCache) -> Self {
+        self.creds_cache = Some(cache);
+        self
+    }
+
+    // Replace the time source, e.g. a `FixedClock` for reproducible headers.
+    pub fn with_clock(mut self, clock: impl Clock + 'static) -> Self {
+        self.clock = Arc::new(clock);
+        self.server_clock = None;
+        self
+    }
+
+    // Correct the current clock by its offset to the server clock, estimated by
+    // `sync_server_time` or `spawn_server_time_sync`. Set any custom clock first.
+    pub fn with_server_time(mut self) -> Self {
+        let clock = Arc::new(OffsetClock::new(self.clock.clone()));
+        self.clock = clock.clone();
+        self.server_clock = Some(clock);
+        self
+    }
+
+    // Replace the order salt source, e.g. a `SeededSalt` for reproducible orders.
+    pub fn with_salt_generator(mut self, salt: impl SaltGenerator + 'static) -> Self {
+        self.salt = Arc::new(salt);
+        self
+    }
+
+    // How long market metadata is cached before being fetched again, clears the cache.
+    pub fn with_metadata_ttl(mut self, ttl: Duration) -> Self {
+        self.metadata = Arc::new(MetadataCache::new(ttl));
+        self
+    }
+
+    // Required for on-chain calls.
+    pub fn with_rpc(mut self, rpc_url: &str) -> Result<Self> {
+        self.rpc_url = Some(rpc_url.parse().map_err(|_| Error::InvalidRpcUrl)?);
+        Ok(self)
+    }
+
+    // Checked against the address derived from the signer when one is set and the chain's factory is known,
+    // as orders from a wrong proxy are rejected.
+    pub fn with_proxy(self, address: &str, sig_type: SignatureType) -> Result<Self> {
+        let actual = Address::from_str(address).map_err(|_| Error::InvalidProxyAddress)?;
+        match self.derive_proxy_address(sig_type) {
+            Ok(expected) if expected != actual => return Err(Error::ProxyAddressMismatch { expected, actual }),
+            Ok(_) | Err(Error::SignerRequired) | Err(Error::ProxyFactoryUnknown) => {},
+            Err(e) => return Err(e),
+        }
+        self.with_proxy_unchecked(address, sig_type)
+    }
+
+    // For wallets not deployed by the Polymarket factories.
+    pub fn with_proxy_unchecked(mut self, address: &str, sig_type: SignatureType) -> Result<Self> {
         if sig_type == SignatureType::EOA {
             return Err(Error::InvalidSignatureType);
         }
@@ -105,12 +283,74 @@ impl ClobClient {
         Ok(self)
     }
 
-    pub fn get_signer(&self) -> Result<&PrivateKeySigner> {
+    // Use the proxy wallet or safe derived from the signer, set the signer first.
+    pub fn with_derived_proxy(mut self, sig_type: SignatureType) -> Result<Self> {
+        let address = self.derive_proxy_address(sig_type)?;
+        self.proxy = Some(Proxy { address, sig_type });
+        Ok(self)
+    }
+
+    // Address of the signer's Polymarket proxy wallet or Gnosis safe.
+    pub fn derive_proxy_address(&self, sig_type: SignatureType) -> Result<Address> {
+        let signer = self.get_signer()?.address();
+        let contracts = self.get_contracts()?;
+        match sig_type {
+            SignatureType::EOA => Err(Error::InvalidSignatureType),
+            SignatureType::PolyProxy => {
+                let factory = contracts.proxy_factory.ok_or(Error::ProxyFactoryUnknown)?;
+                Ok(wallet::proxy_wallet_address(factory, signer))
+            },
+            SignatureType::PolyGnosisSafe => {
+                let factory = contracts.safe_factory.ok_or(Error::ProxyFactoryUnknown)?;
+                Ok(wallet::safe_address(factory, signer))
+            },
+        }
+    }
+
+    pub fn get_signer(&self) -> Result<&dyn ClobSigner> {
         self.signer
-            .as_ref()
+            .as_deref()
             .ok_or(Error::SignerRequired)
     }
 
+    // Provider that signs and sends transactions with the client signer.
+    pub(crate) fn get_provider(&self) -> Result<impl Provider<Http<Client>> + Clone> {
+        let rpc_url = self.rpc_url.clone().ok_or(Error::RpcRequired)?;
+        let signer = self.signer.clone().ok_or(Error::SignerRequired)?;
+        Ok(
+            ProviderBuilder::new()
+                .with_recommended_fillers()
+                .wallet(EthereumWallet::from(TxSignerAdapter(signer)))
+                .on_http(rpc_url)
+        )
+    }
+
+    pub fn get_contracts(&self) -> Result<&ContractConfig> {
+        self.contracts
+            .as_ref()
+            .ok_or(Error::ContractsNotConfigured)
+    }
+
+    // Address funding orders, the proxy wallet if one is set.
+    pub fn get_maker_address(&self) -> Result<Address> {
+        match &self.proxy {
+            Some(proxy) => Ok(proxy.address),
+            None        => Ok(self.get_signer()?.address()),
+        }
+    }
+
+    pub fn get_signature_type(&self) -> SignatureType {
+        self.proxy.as_ref().map_or(SignatureType::EOA, |proxy| proxy.sig_type)
+    }
+
+    // Funds held by a proxy wallet can't be moved by transactions from the signer.
+    pub(crate) fn require_eoa(&self) -> Result<()> {
+        if self.proxy.is_some() {
+            return Err(Error::ProxyUnsupported);
+        }
+        Ok(())
+    }
+
     pub fn get_creds(&self) -> Result<&ApiCreds> {
         self.creds
             .as_ref()
@@ -128,27 +368,66 @@ impl ClobClient {
             _ => return Err(Error::InvalidSignatureType),
         };
 
-        Self::new(&var("CLOB_URL")?)
-            .with_signer(&var("PRIVATE_KEY")?, chain_id)?
-            .with_creds(ApiCreds::from_env()?)
-            .with_proxy(&var("PROXY")?, sig_type)
+        if let Ok(path) = var("CONTRACTS_FILE") {
+            register_contracts_from_file(path)?;
+        }
+
+        let mut client = Self::new(&var("CLOB_URL")?);
+        if let Ok(rpc_url) = var("RPC_URL") {
+            client = client.with_rpc(&rpc_url)?;
+        }
+
+        // Creds are optional so a Level 1 client can create or derive them.
+        let mut client = client.with_signer_from_env(chain_id)?;
+        if let Ok(creds) = ApiCreds::from_env() {
+            client = client.with_creds(creds);
+        }
+
+        match (sig_type, var("PROXY")) {
+            (SignatureType::EOA, _) => Ok(client),
+            (_, Ok(proxy))          => client.with_proxy(&proxy, sig_type),
+            (_, Err(_))             => client.with_derived_proxy(sig_type),
+        }
     }
 
-    #[tracing::instrument(skip(self))]
-    pub async fn request<T: serde::de::DeserializeOwned + std::fmt::Debug>(&self, args: RequestArgs<'_>) -> Result<T> {
-        
-        let headers = match args.auth_level {
+    // Signer source is picked from the first of these set:
+    // `PRIVATE_KEY`, `KEYSTORE_PATH` (with `KEYSTORE_PASSWORD`),
+    // `MNEMONIC` (with optional `DERIVATION_PATH`), `REMOTE_SIGNER_URL` (with `SIGNER_ADDRESS`).
+    fn with_signer_from_env(self, chain_id: u64) -> Result<Self> {
+        if let Ok(private_key) = var("PRIVATE_KEY") {
+            self.with_signer(&Zeroizing::new(private_key), chain_id)
+        } else if let Ok(path) = var("KEYSTORE_PATH") {
+            self.with_keystore(path, &Zeroizing::new(var("KEYSTORE_PASSWORD")?), chain_id)
+        } else if let Ok(phrase) = var("MNEMONIC") {
+            let phrase = Zeroizing::new(phrase);
+            self.with_mnemonic(&phrase, var("DERIVATION_PATH").ok().as_deref(), chain_id)
+        } else if let Ok(url) = var("REMOTE_SIGNER_URL") {
+            self.with_remote_signer(&url, &var("SIGNER_ADDRESS")?, chain_id)
+        } else {
+            Err(Error::SignerRequired)
+        }
+    }
+
+    async fn auth_headers(&self, args: &RequestArgs<'_>) -> Result<Option<HeaderMap>> {
+        let timestamp = self.clock.now().timestamp();
+        Ok(match args.auth_level {
             AuthLevel::None => None,
             AuthLevel::L1 { nonce } => {
                 let signer = self.get_signer()?;
-                Some(auth::l1_headers(signer, nonce)?)
+                Some(auth::l1_headers(signer, nonce, timestamp).await?)
             },
             AuthLevel::L2 => {
                 let signer = self.get_signer()?;
                 let creds = self.get_creds()?;
-                Some(auth::l2_headers(signer, creds, &args.method, args.path, args.body.as_deref())?)
+                Some(auth::l2_headers(signer, creds, &args.method, args.path, args.body.as_deref(), timestamp)?)
             },
-        };
+        })
+    }
+
+    #[tracing::instrument(skip(self))]
+    pub async fn request<T: serde::de::DeserializeOwned + std::fmt::Debug>(&self, args: RequestArgs<'_>) -> Result<T> {
+        
+        let headers = self.auth_headers(&args).await?;
 
         let mut req = self.client.request(args.method, format!("{}{}", &self.base_url, args.path));
         if let Some(headers) = headers {
@@ -160,6 +439,7 @@ impl ClobClient {
         if let Some(queries) = args.queries {
             req = req.query(queries);
         }
+        // Secret bearing auth headers are marked sensitive so only show as such.
         tracing::debug!("request: {:#?}", req);
 
         let resp = req.send().await?;
@@ -174,4 +454,42 @@ impl ClobClient {
         tracing::debug!("response: {:#?}", out);
         Ok(out)
     }
-}
\ No newline at end of file
+}
+
+// Waits for the transaction to be mined, erroring if it reverted.
+pub(crate) async fn confirm_tx(pending: PendingTransactionBuilder<Http<Client>, Ethereum>) -> Result<TxHash> {
+    let receipt = pending.get_receipt().await?;
+    if !receipt.status() {
+        return Err(Error::TransactionReverted(receipt.transaction_hash));
+    }
+    tracing::info!("transaction confirmed: {}", receipt.transaction_hash);
+    Ok(receipt.transaction_hash)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::contracts::{AMOY_CONTRACTS, MATIC_CONTRACTS};
+    use super::*;
+
+    const PRIVATE_KEY: &str = "0xac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80";
+
+    #[test]
+    fn test_contracts_follow_chain() {
+        let client = ClobClient::new("http://localhost");
+        assert!(matches!(client.get_contracts(), Err(Error::ContractsNotConfigured)));
+
+        let client = client.with_signer(PRIVATE_KEY, 137).unwrap();
+        assert_eq!(client.get_contracts().unwrap(), &MATIC_CONTRACTS);
+        let client = client.with_signer(PRIVATE_KEY, 80002).unwrap();
+        assert_eq!(client.get_contracts().unwrap(), &AMOY_CONTRACTS);
+
+        // Custom contracts apply to the chain of the signer set after them, or already set.
+        let custom = ContractConfig { exchange: Address::with_last_byte(1), ..AMOY_CONTRACTS };
+        let client = ClobClient::new("http://localhost").with_contracts(custom).with_signer(PRIVATE_KEY, 80002).unwrap();
+        assert_eq!(client.get_contracts().unwrap(), &custom);
+        let client = client.with_signer(PRIVATE_KEY, 80002).unwrap().with_contracts(custom);
+        assert_eq!(client.get_contracts().unwrap(), &custom);
+        let client = client.with_signer(PRIVATE_KEY, 137).unwrap();
+        assert_eq!(client.get_contracts().unwrap(), &MATIC_CONTRACTS);
+    }
+}
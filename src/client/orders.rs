@@ -1,35 +1,16 @@
-use std::collections::HashMap;
+use std::{collections::HashMap, fs, path::Path, str::FromStr};
 use alloy::primitives::{Address, U256};
-use rand::Rng;
 use reqwest::Method;
 use serde_json::json;
-use crate::{auth, schema::*, Result};
+use serde::{Deserialize, Serialize};
+use crate::{auth, fees::{self, OrderCost}, schema::*, Error, Result};
 use super::{AuthLevel, ClobClient, RequestArgs};
 
-const PUBLIC_TAKER_ADDRESS: Address = Address::ZERO;
+pub use crate::schema::OrderType;
 
-#[derive(Debug, Clone, Copy, PartialEq)]
-pub enum OrderType {
-    /* 
-    A 'Fill-Or-Kill' order is an market order to buy 
-    shares that must be executed immediately in its entirety; 
-    otherwise, the entire order will be cancelled. 
-    */
-    FOK,
-    /* 
-    A 'Good-Til-Cancelled' order is a limit order that is 
-    active until it is fulfilled or cancelled. 
-    */
-    GTC,
-    /* 
-    A 'Good-Til-Day' order is a type of order that is 
-    active until its specified date (UTC seconds timestamp), 
-    unless it has already been fulfilled or cancelled. 
-    */
-    GTD,
-}
+const PUBLIC_TAKER_ADDRESS: Address = Address::ZERO;
 
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub enum SignatureType {
     // EIP712 signature signed by an EOA.
     EOA,
@@ -39,46 +20,78 @@ pub enum SignatureType {
     PolyGnosisSafe,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct OrderArgs {
-    // Prices are multiplied by 1000 (avoids having to deal with tick size).
-    pub price:      u32,
+    // Prices are multiplied by 1000, checked against the tick size when the market metadata is cached.
+    pub price:        u32,
     // Sizes are multiplied by 100 (avoids having to deal with lot size).
-    pub size:       u32,
-    pub buy:        bool,
-    pub asset_id:   String,
-    // Neg risk is related to the asset, endpoint exists in /markets.rs.
-    // Don't retrieve here because it's wasteful.
-    pub neg_risk:   bool,
-    pub expiration: Option<u64>,
-    pub type_:      OrderType,
+    pub size:         u32,
+    pub buy:          bool,
+    pub asset_id:     String,
+    // Looked up from the market metadata cache if none.
+    pub neg_risk:     Option<bool>,
+    // Looked up from the market metadata cache if none.
+    pub fee_rate_bps: Option<u32>,
+    pub expiration:   Option<u64>,
+    #[serde(rename = "type")]
+    pub type_:        OrderType,
+}
+
+// Batch of orders in a file, e.g. in TOML:
+// [[orders]]
+// price = 500
+// ...
+#[derive(Deserialize)]
+struct OrderBatch {
+    orders: Vec<OrderArgs>,
+}
+
+impl OrderArgs {
+
+    // Loads a batch of orders from a JSON or TOML file, by extension.
+    // JSON files are an array of orders, TOML files an `orders` array of tables.
+    pub fn load_batch(path: impl AsRef<Path>) -> Result<Vec<Self>> {
+        let path = path.as_ref();
+        let data = fs::read_to_string(path)?;
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some("json") => Ok(serde_json::from_str(&data)?),
+            Some("toml") => Ok(toml::from_str::<OrderBatch>(&data)?.orders),
+            _            => Err(Error::UnsupportedFileFormat),
+        }
+    }
+
+    // Notional, fee and net proceeds if fully filled at the given fee rate.
+    pub fn cost(&self, fee_rate_bps: u32) -> OrderCost {
+        fees::order_cost(fee_rate_bps, self.buy, self.price as f64 / 1000.0, self.size as f64 / 100.0)
+    }
 }
 
 impl ClobClient {
 
     pub async fn post_order(&self, args: OrderArgs) -> Result<OrderResponse> {
         
-        let signed_order = self.create_signed_order(&args)?;
-        let order_type = match args.type_ {
-            OrderType::FOK => "FOK",
-            OrderType::GTC => "GTC",
-            OrderType::GTD => "GTD",
-        };
+        let signed_order = self.create_signed_order(&args).await?;
+        let token_id = TokenId(signed_order.order.tokenId);
         let api_key = &self.get_creds()?.api_key;
         // TODO: change to formatted for more spped?.
         let body = json!({
             "order":     signed_order,
             "owner":     api_key,
-            "orderType": order_type,
+            "orderType": args.type_,
         }).to_string();
 
-        self.request(RequestArgs {
+        let resp = self.request(RequestArgs {
             method: Method::POST,
             path: "/order",
             queries: None,
             body: Some(body),
             auth_level: AuthLevel::L2,
-        }).await
+        }).await;
+        // A rejection may be due to stale metadata, e.g. the tick size changed.
+        if let Err(Error::ApiError { .. }) = resp {
+            self.invalidate_market_metadata(token_id);
+        }
+        resp
     }
 
     pub async fn cancel_order(&self, order_id: &str) -> Result<CancelResponse> {
@@ -138,7 +151,7 @@ impl ClobClient {
         market: Option<&str>,
     ) -> Result<OpenOrders> {
 
-        let mut queries = vec![("next_cursor", next_cursor.unwrap_or("MA=="))];
+        let mut queries = vec![("next_cursor", next_cursor.unwrap_or(START_CURSOR))];
         if let Some(asset_id) = asset_id {
             queries.push(("asset_id", asset_id));
         }
@@ -184,7 +197,16 @@ impl ClobClient {
         }).await
     }
     
-    fn create_signed_order(&self, args: &OrderArgs) -> Result<SignedOrder> {
+    // Cost of the order at its fee rate, looked up as when signing if not set.
+    pub async fn estimate_order_cost(&self, args: &OrderArgs) -> Result<OrderCost> {
+        let fee_rate_bps = match args.fee_rate_bps {
+            Some(fee_rate_bps) => fee_rate_bps,
+            None => self.get_market_metadata(TokenId::from_str(&args.asset_id)?).await?.fee_rate_bps(),
+        };
+        Ok(args.cost(fee_rate_bps))
+    }
+
+    async fn create_signed_order(&self, args: &OrderArgs) -> Result<SignedOrder> {
         
         let (maker_amount, taker_amount) = {
             if args.buy {
@@ -194,6 +216,19 @@ impl ClobClient {
             }
         };
 
+        let token_id = TokenId::from_str(&args.asset_id)?;
+        // Only fetched if something is left to look up.
+        let metadata = match (args.neg_risk, args.fee_rate_bps) {
+            (Some(_), Some(_)) => self.get_cached_market_metadata(token_id),
+            _ => Some(self.get_market_metadata(token_id).await?),
+        };
+        if let Some(metadata) = &metadata {
+            metadata.check_price(args.price)?;
+        }
+        // Unwraps safe, metadata is fetched when either is none.
+        let neg_risk = args.neg_risk.unwrap_or_else(|| metadata.as_ref().unwrap().neg_risk);
+        let fee_rate_bps = args.fee_rate_bps.unwrap_or_else(|| metadata.as_ref().unwrap().fee_rate_bps());
+
         // Unwrap safe, checked prior to calling.
         let signer_address = self.get_signer()?.address();
         let (maker_address, sig_type) = if let Some(proxy) = &self.proxy {
@@ -202,25 +237,143 @@ impl ClobClient {
             (signer_address, SignatureType::EOA)
         };
 
-        // TODO: is this right?
-        let salt: u32 = rand::thread_rng().gen();
-    
         let raw_order = Order {
             takerAmount:    taker_amount,
             makerAmount:    maker_amount,
-            salt:           U256::from(salt),
+            salt:           self.salt.next_salt(),
             maker:          maker_address,
             signer:         signer_address,
             taker:          PUBLIC_TAKER_ADDRESS,
-            tokenId:        U256::from_str_radix(&args.asset_id, 10)?,
+            tokenId:        token_id.0,
             expiration:     U256::from(args.expiration.unwrap_or(0)),
-            nonce:          U256::ZERO,
-            feeRateBps:     U256::ZERO,
+            nonce:          self.get_nonces().get(neg_risk),
+            feeRateBps:     U256::from(fee_rate_bps),
             signatureType:  sig_type as u8,
             side:           if args.buy { 0 } else { 1 },
         };
     
-        auth::sign_order(self.get_signer()?, raw_order, args.neg_risk)
+        auth::sign_order(self.get_signer()?, raw_order, self.get_contracts()?, neg_risk).await
     }
 }
 
+#[cfg(test)]
+mod tests {
+    use crate::{auth, client::Nonces, clock::FixedClock, salt::FixedSalt};
+    use super::*;
+
+    // Known private key for testing.
+    const PRIVATE_KEY: &str = "0xac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80";
+
+    fn get_client() -> ClobClient {
+        ClobClient::new("http://localhost")
+            .with_signer(PRIVATE_KEY, 80002).unwrap()
+            .with_clock(FixedClock::from_timestamp(1000000))
+            .with_salt_generator(FixedSalt(U256::from(479249096354_u64)))
+    }
+
+    fn get_args() -> OrderArgs {
+        OrderArgs {
+            price:        500,
+            size:         10000,
+            buy:          true,
+            asset_id:     "1234".to_string(),
+            neg_risk:     Some(false),
+            fee_rate_bps: Some(0),
+            expiration:   None,
+            type_:        OrderType::GTC,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_create_signed_order_deterministic() {
+        let client = get_client();
+        let signed_order = client.create_signed_order(&get_args()).await.unwrap();
+        assert_eq!(signed_order.order.makerAmount, U256::from(50000000));
+        assert_eq!(signed_order.order.takerAmount, U256::from(100000000));
+        assert_eq!(signed_order.signature, client.create_signed_order(&get_args()).await.unwrap().signature);
+        assert_eq!(signed_order.signature, "0x34396682d4fe8b376b6d45d248e9a33e2cacd61145dde15235283b1a9676fd396bc201afde0f5e8c5d96d13aff3900b4d4c8ff2a726377516ebac959076cb3ab1c");
+        auth::verify_order(&signed_order, 80002, false).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_create_signed_order_uses_nonce() {
+        let client = get_client().with_nonces(Nonces { exchange: U256::from(2), neg_risk_exchange: U256::from(5) });
+        assert_eq!(client.create_signed_order(&get_args()).await.unwrap().order.nonce, U256::from(2));
+        let args = OrderArgs { neg_risk: Some(true), ..get_args() };
+        assert_eq!(client.create_signed_order(&args).await.unwrap().order.nonce, U256::from(5));
+    }
+
+    #[tokio::test]
+    async fn test_auth_headers_deterministic() {
+        let client = get_client();
+        let args = RequestArgs {
+            method:     Method::GET,
+            path:       "/auth/derive-api-key",
+            queries:    None,
+            body:       None,
+            auth_level: AuthLevel::L1 { nonce: U256::ZERO },
+        };
+        let headers = client.auth_headers(&args).await.unwrap().unwrap();
+        assert_eq!(headers["POLY_TIMESTAMP"], "1000000");
+        assert_eq!(headers["POLY_SIGNATURE"].to_str().unwrap(), "0x8755b18be8285606b28b43f5bcadb6761ce3d1eeed5cc9dcc737be7ecf67867f384ba41de03355efc91550cda83587b911347f4c615b6122e85120cdeef89b691b");
+    }
+
+    #[tokio::test]
+    async fn test_create_signed_order_cached_metadata() {
+        let client = get_client();
+        client.cache_market(get_market());
+
+        // Neg risk and fee rate are looked up rather than fetched.
+        let args = OrderArgs { asset_id: "1".to_string(), neg_risk: None, fee_rate_bps: None, ..get_args() };
+        let signed_order = client.create_signed_order(&args).await.unwrap();
+        assert_eq!(signed_order.order.feeRateBps, U256::from(200));
+        let expected = OrderArgs { neg_risk: Some(true), fee_rate_bps: Some(200), ..args.clone() };
+        assert_eq!(signed_order.signature, client.create_signed_order(&expected).await.unwrap().signature);
+        let overridden = OrderArgs { fee_rate_bps: Some(100), ..args.clone() };
+        assert_eq!(client.create_signed_order(&overridden).await.unwrap().order.feeRateBps, U256::from(100));
+        assert_eq!(client.estimate_order_cost(&args).await.unwrap(), args.cost(200));
+
+        let off_tick = OrderArgs { price: 505, ..args };
+        assert!(matches!(client.create_signed_order(&off_tick).await, Err(Error::InvalidTickSize(_))));
+    }
+
+    #[test]
+    fn test_load_batch() {
+        let dir = std::env::temp_dir().join(format!("clob-orders-{}", rand::random::<u64>()));
+        fs::create_dir_all(&dir).unwrap();
+
+        let toml = r#"
+            [[orders]]
+            price    = 500
+            size     = 1000
+            buy      = true
+            asset_id = "1234"
+            neg_risk = false
+            type     = "GTC"
+
+            [[orders]]
+            price      = 600
+            size       = 500
+            buy        = false
+            asset_id   = "1234"
+            neg_risk   = false
+            expiration = 1700000000
+            type       = "GTD"
+        "#;
+        fs::write(dir.join("orders.toml"), toml).unwrap();
+        let orders = OrderArgs::load_batch(dir.join("orders.toml")).unwrap();
+        assert_eq!(orders.len(), 2);
+        assert_eq!(orders[1].expiration, Some(1700000000));
+        assert_eq!(orders[1].type_, OrderType::GTD);
+
+        // Written back as JSON, loads the same.
+        fs::write(dir.join("orders.json"), serde_json::to_string(&orders).unwrap()).unwrap();
+        let reloaded = OrderArgs::load_batch(dir.join("orders.json")).unwrap();
+        assert_eq!(serde_json::to_value(&reloaded).unwrap(), serde_json::to_value(&orders).unwrap());
+
+        assert!(matches!(OrderArgs::load_batch(dir.join("orders.yaml")), Err(Error::IoError(_))));
+        fs::write(dir.join("orders.yaml"), "").unwrap();
+        assert!(matches!(OrderArgs::load_batch(dir.join("orders.yaml")), Err(Error::UnsupportedFileFormat)));
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
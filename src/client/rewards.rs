@@ -0,0 +1,61 @@
+use std::collections::HashMap;
+use chrono::NaiveDate;
+use reqwest::Method;
+use crate::{
+    rewards::{self, Quote, ScoreEstimate},
+    schema::{ConditionId, TokenId, TotalUserEarning, UserEarnings, START_CURSOR},
+    Result,
+};
+use super::{AuthLevel, ClobClient, RequestArgs};
+
+impl ClobClient {
+
+    // A page of the maker's reward earnings per market on the day.
+    pub async fn get_user_earnings(&self, date: NaiveDate, next_cursor: Option<&str>) -> Result<UserEarnings> {
+        let date = date.to_string();
+        let sig_type = (self.get_signature_type() as u8).to_string();
+        self.request(RequestArgs {
+            method: Method::GET,
+            path: "/rewards/user",
+            queries: Some(&[
+                ("date", &date),
+                ("signature_type", &sig_type),
+                ("next_cursor", next_cursor.unwrap_or(START_CURSOR)),
+            ]),
+            body: None,
+            auth_level: AuthLevel::L2,
+        }).await
+    }
+
+    // The maker's reward earnings across all markets on the day.
+    pub async fn get_user_total_earnings(&self, date: NaiveDate) -> Result<Vec<TotalUserEarning>> {
+        let date = date.to_string();
+        let sig_type = (self.get_signature_type() as u8).to_string();
+        self.request(RequestArgs {
+            method: Method::GET,
+            path: "/rewards/user/total",
+            queries: Some(&[("date", &date), ("signature_type", &sig_type)]),
+            body: None,
+            auth_level: AuthLevel::L2,
+        }).await
+    }
+
+    // The maker's current share of each market's rewards, in percent.
+    pub async fn get_reward_percentages(&self) -> Result<HashMap<ConditionId, f64>> {
+        let sig_type = (self.get_signature_type() as u8).to_string();
+        self.request(RequestArgs {
+            method: Method::GET,
+            path: "/rewards/user/percentages",
+            queries: Some(&[("signature_type", &sig_type)]),
+            body: None,
+            auth_level: AuthLevel::L2,
+        }).await
+    }
+
+    // Scores quotes of the token would earn against its current book, none if the book has no midpoint.
+    pub async fn estimate_reward_score(&self, token_id: TokenId, quotes: &[Quote]) -> Result<Option<ScoreEstimate>> {
+        let metadata = self.get_market_metadata(token_id).await?;
+        let book = self.get_market_book(&token_id.to_string()).await?;
+        Ok(rewards::estimate_quotes(&metadata.market.rewards, &book, quotes))
+    }
+}
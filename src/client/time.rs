@@ -0,0 +1,49 @@
+use std::time::Duration;
+use reqwest::Method;
+use tokio::task::JoinHandle;
+use crate::{clock::ClockSample, Error, Result};
+use super::{AuthLevel, ClobClient, RequestArgs};
+
+impl ClobClient {
+
+    // Server time in UTC seconds.
+    pub async fn get_server_time(&self) -> Result<i64> {
+        self.request(RequestArgs {
+            method: Method::GET,
+            path: "/time",
+            queries: None,
+            body: None,
+            auth_level: AuthLevel::None,
+        }).await
+    }
+
+    // Samples the server time once and updates the clock offset estimate.
+    // Requires `with_server_time`.
+    pub async fn sync_server_time(&self) -> Result<ClockSample> {
+        let clock = self.server_clock.as_ref().ok_or(Error::ServerTimeRequired)?;
+
+        let sent = clock.base_now();
+        let server_secs = self.get_server_time().await?;
+        let received = clock.base_now();
+
+        let sample = ClockSample::new(sent, received, server_secs);
+        clock.add_sample(sample);
+        tracing::debug!("server time sample: {:?}, offset: {}", sample, clock.offset());
+        Ok(sample)
+    }
+
+    // Keeps the clock offset estimate up to date in the background, first sample is taken immediately.
+    // Failed samples are logged and skipped.
+    pub fn spawn_server_time_sync(&self, period: Duration) -> JoinHandle<()> {
+        let client = self.clone();
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(period);
+            loop {
+                interval.tick().await;
+                if let Err(e) = client.sync_server_time().await {
+                    tracing::warn!("server time sync failed: {}", e);
+                }
+            }
+        })
+    }
+}
@@ -1,7 +1,7 @@
 use alloy::primitives::U256;
 use reqwest::Method;
 use serde::Deserialize;
-use crate::{Error, Result};
+use crate::{schema::{AssetType, BalanceAllowance}, Error, Result};
 use super::{ApiCreds, AuthLevel, ClobClient, RequestArgs};
 
 impl ClobClient {
@@ -47,7 +47,32 @@ impl ClobClient {
         }).await
     }
 
-    // Deletes the API key used to authenticate the request.
+    // Derives creds for the nonce, creating them if none exist.
+    // Checks the creds cache first if one is set, and stores the result in it.
+    // An unreadable cache entry is logged and replaced.
+    pub async fn create_or_derive_creds(&self, nonce: U256) -> Result<ApiCreds> {
+        let signer = self.get_signer()?;
+        if let Some(cache) = &self.creds_cache {
+            match cache.load(signer, &self.base_url, nonce).await {
+                Ok(Some(creds)) => return Ok(creds),
+                Ok(None) => {},
+                Err(e) => tracing::warn!("ignoring cached creds: {}", e),
+            }
+        }
+
+        let creds = match self.derive_creds_from_nonce(nonce).await {
+            Ok(creds) => creds,
+            Err(Error::ApiError { status_code: 404, .. }) => self.create_creds_with_nonce(nonce).await?,
+            Err(e) => return Err(e),
+        };
+
+        if let Some(cache) = &self.creds_cache {
+            cache.store(signer, &self.base_url, nonce, &creds).await?;
+        }
+        Ok(creds)
+    }
+
+    // Deletes the API key used to authenticate the request, and any cached copy of it.
     pub async fn delete_api_key(&self) -> Result<()> {
 
         let resp = self.request::<String>(RequestArgs {
@@ -59,10 +84,12 @@ impl ClobClient {
         }).await?;
 
         if resp != "OK" {
-            Err(Error::ApiKeyDeleteFailed)
-        } else {
-            Ok(())
+            return Err(Error::ApiKeyDeleteFailed);
+        }
+        if let Some(cache) = &self.creds_cache {
+            cache.remove_api_key(self.get_signer()?, &self.base_url, &self.get_creds()?.api_key).await?;
         }
+        Ok(())
     }
 
     pub async fn require_cert(&self) -> Result<bool> {
@@ -85,17 +112,22 @@ impl ClobClient {
         )
     }
 
-    pub async fn get_balance_allowance(&self) -> Result<()> {
-        let resp = self.request::<String>(RequestArgs {
+    // Balance and exchange allowances of the maker, token id is required for conditional tokens.
+    pub async fn get_balance_allowance(&self, asset_type: AssetType, token_id: Option<&str>) -> Result<BalanceAllowance> {
+        let sig_type = (self.get_signature_type() as u8).to_string();
+        let mut queries = vec![("asset_type", asset_type.as_str()), ("signature_type", &sig_type)];
+        if let Some(token_id) = token_id {
+            queries.push(("token_id", token_id));
+        }
+
+        self.request(RequestArgs {
             method: Method::GET,
             path: "/balance-allowance",
-            queries: None,
+            queries: Some(&queries),
             body: None,
             auth_level: AuthLevel::L2,
-        }).await?;
-
-        println!("{}", resp);
-        Ok(())
+        }).await
     }
+
     // TODO: Get trades.
 }
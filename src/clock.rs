@@ -0,0 +1,142 @@
+use std::{collections::VecDeque, fmt, sync::{atomic::{AtomicI64, Ordering}, Arc, Mutex}};
+use chrono::{DateTime, Duration, Utc};
+
+// Number of recent server time samples the offset is estimated from.
+const MAX_SAMPLES: usize = 8;
+
+// Source of the current time for auth header timestamps.
+pub trait Clock: Send + Sync + fmt::Debug {
+    fn now(&self) -> DateTime<Utc>;
+}
+
+// Local system time.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> DateTime<Utc> {
+        Utc::now()
+    }
+}
+
+// Always returns the same time, for reproducible tests.
+#[derive(Debug, Clone, Copy)]
+pub struct FixedClock(pub DateTime<Utc>);
+
+impl FixedClock {
+    pub fn from_timestamp(secs: i64) -> Self {
+        Self(DateTime::from_timestamp(secs, 0).unwrap_or_default())
+    }
+}
+
+impl Clock for FixedClock {
+    fn now(&self) -> DateTime<Utc> {
+        self.0
+    }
+}
+
+// Single measurement of the server clock against the local one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ClockSample {
+    // Server time minus local time.
+    pub offset_ms: i64,
+    // Round trip time of the request the sample was taken from.
+    pub rtt_ms:    i64,
+}
+
+impl ClockSample {
+    // Server time is assumed to be read halfway through the round trip.
+    // The server only reports whole seconds, so half a second is added to centre the error.
+    pub fn new(sent: DateTime<Utc>, received: DateTime<Utc>, server_secs: i64) -> Self {
+        let rtt_ms = (received - sent).num_milliseconds();
+        let local_ms = sent.timestamp_millis() + rtt_ms / 2;
+        Self {
+            offset_ms: server_secs * 1000 + 500 - local_ms,
+            rtt_ms,
+        }
+    }
+}
+
+// Corrects a base clock by the estimated offset to the server clock.
+// The estimate comes from the lowest latency of the recent samples, as it has the least uncertainty.
+#[derive(Debug)]
+pub struct OffsetClock {
+    base:      Arc<dyn Clock>,
+    offset_ms: AtomicI64,
+    samples:   Mutex<VecDeque<ClockSample>>,
+}
+
+impl OffsetClock {
+
+    pub fn new(base: Arc<dyn Clock>) -> Self {
+        Self {
+            base,
+            offset_ms: AtomicI64::new(0),
+            samples:   Mutex::new(VecDeque::with_capacity(MAX_SAMPLES)),
+        }
+    }
+
+    // Uncorrected time, used to take samples.
+    pub fn base_now(&self) -> DateTime<Utc> {
+        self.base.now()
+    }
+
+    pub fn offset(&self) -> Duration {
+        Duration::milliseconds(self.offset_ms.load(Ordering::Relaxed))
+    }
+
+    pub fn add_sample(&self, sample: ClockSample) {
+        let mut samples = self.samples.lock().unwrap_or_else(|e| e.into_inner());
+        if samples.len() == MAX_SAMPLES {
+            samples.pop_front();
+        }
+        samples.push_back(sample);
+
+        if let Some(best) = samples.iter().min_by_key(|s| s.rtt_ms) {
+            self.offset_ms.store(best.offset_ms, Ordering::Relaxed);
+        }
+    }
+}
+
+impl Clock for OffsetClock {
+    fn now(&self) -> DateTime<Utc> {
+        self.base.now() + self.offset()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(millis: i64) -> DateTime<Utc> {
+        DateTime::from_timestamp_millis(millis).unwrap()
+    }
+
+    #[test]
+    fn test_clock_sample() {
+        // Local clock is 10s behind, request took 200ms.
+        let sample = ClockSample::new(at(1_000_000), at(1_000_200), 1_010);
+        assert_eq!(sample.rtt_ms, 200);
+        assert_eq!(sample.offset_ms, 10_400);
+    }
+
+    #[test]
+    fn test_offset_clock_uses_lowest_rtt() {
+        let clock = OffsetClock::new(Arc::new(FixedClock::from_timestamp(1_000)));
+        assert_eq!(clock.now(), at(1_000_000));
+
+        clock.add_sample(ClockSample { offset_ms: 5_000, rtt_ms: 300 });
+        assert_eq!(clock.now(), at(1_005_000));
+
+        clock.add_sample(ClockSample { offset_ms: 4_000, rtt_ms: 50 });
+        clock.add_sample(ClockSample { offset_ms: 6_000, rtt_ms: 900 });
+        assert_eq!(clock.now(), at(1_004_000));
+        assert_eq!(clock.base_now(), at(1_000_000));
+
+        // Low latency sample ages out of the window.
+        for _ in 0..MAX_SAMPLES {
+            clock.add_sample(ClockSample { offset_ms: 3_000, rtt_ms: 100 });
+        }
+        assert_eq!(clock.offset(), Duration::seconds(3));
+    }
+}
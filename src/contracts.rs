@@ -1,15 +1,23 @@
+use std::{collections::HashMap, fs, path::Path, sync::{LazyLock, RwLock}};
 use alloy::primitives::Address;
+use serde::{Deserialize, Serialize};
 use crate::{Result, Error};
 
-pub const SUPPORTED_CHAIN_IDS: [u64; 2] = [137, 80002];
+// Deployments registered at runtime, e.g. for local devnets.
+static CUSTOM_CONTRACTS: LazyLock<RwLock<HashMap<u64, ContractConfig>>> = LazyLock::new(Default::default);
 
-#[allow(unused)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub struct ContractConfig {
     pub exchange:           Address,
     pub neg_risk_adapter:   Address,
     pub neg_risk_exchange:  Address,
     pub collateral:         Address,
     pub conditional_tokens: Address,
+    // Factories deploying Polymarket proxy wallets and Gnosis safes, none if not deployed on the chain.
+    #[serde(default)]
+    pub proxy_factory:      Option<Address>,
+    #[serde(default)]
+    pub safe_factory:       Option<Address>,
 }
 
 pub const MATIC_CONTRACTS: ContractConfig = ContractConfig {
@@ -18,6 +26,8 @@ pub const MATIC_CONTRACTS: ContractConfig = ContractConfig {
     neg_risk_exchange:  Address::new([197, 213, 99, 163, 106, 231, 129, 69, 196, 90, 80, 19, 77, 72, 161, 33, 82, 32, 248, 10]),
     collateral:         Address::new([39, 145, 188, 161, 242, 222, 70, 97, 237, 136, 163, 12, 153, 167, 169, 68, 154, 168, 65, 116]),
     conditional_tokens: Address::new([77, 151, 220, 217, 126, 201, 69, 244, 12, 246, 95, 135, 9, 122, 206, 94, 160, 71, 96, 69]),
+    proxy_factory:      Some(Address::new([171, 69, 197, 164, 176, 201, 65, 162, 242, 49, 192, 76, 63, 73, 24, 46, 26, 37, 64, 82])),
+    safe_factory:       Some(Address::new([170, 207, 238, 160, 62, 177, 86, 28, 78, 103, 214, 97, 228, 6, 130, 189, 32, 227, 84, 27])),
 };
 
 pub const AMOY_CONTRACTS: ContractConfig = ContractConfig {
@@ -26,14 +36,44 @@ pub const AMOY_CONTRACTS: ContractConfig = ContractConfig {
     neg_risk_exchange:  Address::new([197, 213, 99, 163, 106, 231, 129, 69, 196, 90, 80, 19, 77, 72, 161, 33, 82, 32, 248, 10]),
     collateral:         Address::new([156, 78, 23, 3, 71, 110, 135, 80, 112, 238, 37, 181, 106, 88, 176, 8, 207, 184, 250, 120]),
     conditional_tokens: Address::new([105, 48, 143, 181, 18, 81, 142, 57, 249, 177, 97, 18, 250, 141, 153, 79, 78, 43, 248, 187]),
+    proxy_factory:      None,
+    safe_factory:       None,
 };
 
+// Built in deployments by chain id.
+const BUILTIN_CONTRACTS: [(u64, ContractConfig); 2] = [
+    (137,   MATIC_CONTRACTS),
+    (80002, AMOY_CONTRACTS),
+];
+
+#[deprecated(note = "only lists the built in deployments, use `get_contracts` to check a chain id")]
+pub const SUPPORTED_CHAIN_IDS: [u64; 2] = [BUILTIN_CONTRACTS[0].0, BUILTIN_CONTRACTS[1].0];
+
+// Registered contracts take precedence over the built in Polygon and Amoy deployments.
 pub fn get_contracts(chain_id: u64) -> Result<ContractConfig> {
-    match chain_id {
-        137   => Ok(MATIC_CONTRACTS),
-        80002 => Ok(AMOY_CONTRACTS),
-        _     => Err(Error::InvalidChainId),
+    if let Some(config) = CUSTOM_CONTRACTS.read().unwrap_or_else(|e| e.into_inner()).get(&chain_id) {
+        return Ok(*config);
+    }
+    BUILTIN_CONTRACTS.iter()
+        .find(|(id, _)| *id == chain_id)
+        .map(|(_, config)| *config)
+        .ok_or(Error::InvalidChainId)
+}
+
+pub fn register_contracts(chain_id: u64, config: ContractConfig) {
+    CUSTOM_CONTRACTS.write().unwrap_or_else(|e| e.into_inner()).insert(chain_id, config);
+}
+
+// Registers every deployment in a JSON file mapping chain id to config, e.g.
+// `{"31337": {"exchange": "0x..", "neg_risk_adapter": "0x..", ...}}`.
+// Returns the chain ids registered.
+pub fn register_contracts_from_file(path: impl AsRef<Path>) -> Result<Vec<u64>> {
+    let configs: HashMap<u64, ContractConfig> = serde_json::from_str(&fs::read_to_string(path)?)?;
+    let chain_ids = configs.keys().copied().collect();
+    for (chain_id, config) in configs {
+        register_contracts(chain_id, config);
     }
+    Ok(chain_ids)
 }
 
 #[cfg(test)]
@@ -48,6 +88,8 @@ mod tests {
         assert_eq!(MATIC_CONTRACTS.neg_risk_exchange,  Address::from_str("0xC5d563A36AE78145C45a50134d48A1215220f80a").unwrap());
         assert_eq!(MATIC_CONTRACTS.collateral,         Address::from_str("0x2791Bca1f2de4661ED88A30C99A7a9449Aa84174").unwrap());
         assert_eq!(MATIC_CONTRACTS.conditional_tokens, Address::from_str("0x4D97DCd97eC945f40cF65F87097ACe5EA0476045").unwrap());
+        assert_eq!(MATIC_CONTRACTS.proxy_factory,      Some(Address::from_str("0xaB45c5A4B0c941a2F231C04C3f49182e1A254052").unwrap()));
+        assert_eq!(MATIC_CONTRACTS.safe_factory,       Some(Address::from_str("0xaacFeEa03eb1561C4e67d661e40682Bd20E3541b").unwrap()));
     }
 
     #[test]
@@ -58,4 +100,37 @@ mod tests {
         assert_eq!(AMOY_CONTRACTS.collateral,         Address::from_str("0x9c4e1703476e875070ee25b56a58b008cfb8fa78").unwrap());
         assert_eq!(AMOY_CONTRACTS.conditional_tokens, Address::from_str("0x69308FB512518e39F9b16112fA8d994F4e2Bf8bB").unwrap());
     }
+
+    #[test]
+    #[allow(deprecated)]
+    fn test_supported_chain_ids() {
+        assert_eq!(SUPPORTED_CHAIN_IDS, [137, 80002]);
+        assert_eq!(get_contracts(137).unwrap(), MATIC_CONTRACTS);
+        assert_eq!(get_contracts(80002).unwrap(), AMOY_CONTRACTS);
+    }
+
+    #[test]
+    fn test_register_contracts_from_file() {
+        let path = std::env::temp_dir().join(format!("clob-contracts-{}.json", rand::random::<u64>()));
+        // Registrations are global, a chain id of its own keeps this test apart from any other.
+        let chain_id = 1_000_000 + rand::random::<u32>() as u64;
+        let config = r#"{
+            "CHAIN_ID": {
+                "exchange":           "0x0000000000000000000000000000000000000001",
+                "neg_risk_adapter":   "0x0000000000000000000000000000000000000002",
+                "neg_risk_exchange":  "0x0000000000000000000000000000000000000003",
+                "collateral":         "0x0000000000000000000000000000000000000004",
+                "conditional_tokens": "0x0000000000000000000000000000000000000005"
+            }
+        }"#.replace("CHAIN_ID", &chain_id.to_string());
+        std::fs::write(&path, config).unwrap();
+
+        assert!(get_contracts(chain_id).is_err());
+        assert_eq!(register_contracts_from_file(&path).unwrap(), vec![chain_id]);
+        let contracts = get_contracts(chain_id).unwrap();
+        assert_eq!(contracts.exchange, Address::with_last_byte(1));
+        assert_eq!(contracts.conditional_tokens, Address::with_last_byte(5));
+        assert_eq!(contracts.proxy_factory, None);
+        std::fs::remove_file(path).unwrap();
+    }
 }
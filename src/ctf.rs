@@ -0,0 +1,179 @@
+use alloy::primitives::{keccak256, uint, Address, B256, U256};
+use crate::{Error, Result};
+
+// Offline derivation of conditional token ids, mirroring `CTHelpers` of the Gnosis conditional tokens contract.
+
+// Field modulus of the alt_bn128 curve y^2 = x^3 + 3 that collection ids are points on.
+const P: U256 = uint!(21888242871839275222246405745257275088696311157297823662689037894645226208583_U256);
+const B: U256 = uint!(3_U256);
+
+// Index sets of the two outcomes of a binary condition.
+pub const BINARY_INDEX_SETS: [u8; 2] = [1, 2];
+
+pub fn condition_id(oracle: Address, question_id: B256, outcome_slot_count: u32) -> B256 {
+    let mut data = Vec::with_capacity(84);
+    data.extend_from_slice(oracle.as_slice());
+    data.extend_from_slice(question_id.as_slice());
+    data.extend_from_slice(&U256::from(outcome_slot_count).to_be_bytes::<32>());
+    keccak256(data)
+}
+
+// Collection ids are compressed curve points so that collections of nested conditions
+// can be combined by point addition regardless of the order they were added in.
+// A zero parent is the root collection, as used by all Polymarket positions.
+pub fn collection_id(parent_collection_id: B256, condition_id: B256, index_set: U256) -> Result<B256> {
+    let mut data = [0u8; 64];
+    data[..32].copy_from_slice(condition_id.as_slice());
+    data[32..].copy_from_slice(&index_set.to_be_bytes::<32>());
+
+    let mut x1 = U256::from_be_bytes(keccak256(data).0);
+    let odd = x1.bit(255);
+    let mut y1;
+    let mut yy;
+    // Increment x until it lands on the curve.
+    loop {
+        x1 = x1.add_mod(U256::from(1), P);
+        yy = curve_y_squared(x1);
+        y1 = sqrt(yy);
+        if y1.mul_mod(y1, P) == yy {
+            break;
+        }
+    }
+    if odd != y1.bit(0) {
+        y1 = P - y1;
+    }
+
+    let x2 = U256::from_be_bytes(parent_collection_id.0);
+    if x2 != U256::ZERO {
+        let odd = x2.bit(254);
+        let x2 = (x2 << 2) >> 2;
+        let yy = curve_y_squared(x2);
+        let mut y2 = sqrt(yy);
+        if odd != y2.bit(0) {
+            y2 = P - y2;
+        }
+        if y2.mul_mod(y2, P) != yy {
+            return Err(Error::InvalidCollectionId);
+        }
+        (x1, y1) = ec_add((x1, y1), (x2, y2));
+    }
+
+    if y1.bit(0) {
+        x1 ^= U256::from(1) << 254;
+    }
+    Ok(B256::from(x1))
+}
+
+// ERC-1155 token id of the position, this is the CLOB token id.
+pub fn position_id(collateral: Address, collection_id: B256) -> U256 {
+    let mut data = Vec::with_capacity(52);
+    data.extend_from_slice(collateral.as_slice());
+    data.extend_from_slice(collection_id.as_slice());
+    U256::from_be_bytes(keccak256(data).0)
+}
+
+// Token ids of the first and second outcome of a binary condition.
+// Neg risk markets use the neg risk adapter's wrapped collateral rather than USDC.
+pub fn binary_position_ids(collateral: Address, condition_id: B256) -> Result<[U256; 2]> {
+    let mut ids = [U256::ZERO; 2];
+    for (id, index_set) in ids.iter_mut().zip(BINARY_INDEX_SETS) {
+        *id = position_id(collateral, collection_id(B256::ZERO, condition_id, U256::from(index_set))?);
+    }
+    Ok(ids)
+}
+
+fn curve_y_squared(x: U256) -> U256 {
+    x.mul_mod(x.mul_mod(x, P), P).add_mod(B, P)
+}
+
+// P = 3 mod 4 so a square root, if one exists, is x^((P + 1) / 4).
+fn sqrt(x: U256) -> U256 {
+    x.pow_mod((P + U256::from(1)) >> 2, P)
+}
+
+fn inverse(x: U256) -> U256 {
+    x.pow_mod(P - U256::from(2), P)
+}
+
+fn sub_mod(a: U256, b: U256) -> U256 {
+    a.add_mod(P - b, P)
+}
+
+// Affine point addition, the point at infinity is (0, 0) as with the ecAdd precompile.
+fn ec_add((x1, y1): (U256, U256), (x2, y2): (U256, U256)) -> (U256, U256) {
+    if x1 == U256::ZERO && y1 == U256::ZERO {
+        return (x2, y2);
+    }
+    if x2 == U256::ZERO && y2 == U256::ZERO {
+        return (x1, y1);
+    }
+
+    let lambda = if x1 == x2 {
+        if y1.add_mod(y2, P) == U256::ZERO {
+            return (U256::ZERO, U256::ZERO);
+        }
+        // Doubling, lambda = 3x^2 / 2y.
+        U256::from(3).mul_mod(x1.mul_mod(x1, P), P).mul_mod(inverse(y1.add_mod(y1, P)), P)
+    } else {
+        sub_mod(y2, y1).mul_mod(inverse(sub_mod(x2, x1)), P)
+    };
+
+    let x3 = sub_mod(sub_mod(lambda.mul_mod(lambda, P), x1), x2);
+    let y3 = sub_mod(lambda.mul_mod(sub_mod(x1, x3), P), y1);
+    (x3, y3)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+    use super::*;
+
+    // Neg risk adapter's wrapped collateral and neg risk adapter on Polygon.
+    const WRAPPED_COLLATERAL: &str = "0x3A3BD7bb9528E159577F7C2e685CC81A765002E2";
+    const NEG_RISK_ADAPTER: &str = "0xd91E80cF2E7be2e162c6513ceD06f1dD0dA35296";
+
+    // "Will Donald Trump win the 2024 US Presidential Election?", a neg risk market, its
+    // question id on the neg risk adapter, condition id, collection ids and CLOB token ids.
+    const QUESTION_ID: &str = "0xe3b1bc389210504ebcb9cffe4b0ed06ccac50561e0f24abb6379984cec030f00";
+    const CONDITION_ID: &str = "0xdd22472e552920b8438158ea7238bfadfa4f736aa4cee91a6b86c39ead110917";
+    const COLLECTION_IDS: [&str; 2] = [
+        "0x13c5bd8e1449325256f875332875131b41b7ff90b7ec816a38259785663eb2d8",
+        "0x679fe1f869287ffd909cce0e21e144a5801ccc3a6934d482ce01f8bacf9ed144",
+    ];
+    const TOKEN_IDS: [&str; 2] = [
+        "21742633143463906290569050155826241533067272736897614950488156847949938836455",
+        "48331043336612883890938759509493159234755048973500640148014422747788308965732",
+    ];
+
+    #[test]
+    fn test_condition_id() {
+        let oracle = Address::from_str(NEG_RISK_ADAPTER).unwrap();
+        let question_id = B256::from_str(QUESTION_ID).unwrap();
+        assert_eq!(condition_id(oracle, question_id, 2), B256::from_str(CONDITION_ID).unwrap());
+        assert_ne!(condition_id(oracle, question_id, 3), B256::from_str(CONDITION_ID).unwrap());
+    }
+
+    #[test]
+    fn test_binary_position_ids_known_market() {
+        let collateral = Address::from_str(WRAPPED_COLLATERAL).unwrap();
+        let condition_id = B256::from_str(CONDITION_ID).unwrap();
+        for ((index_set, collection), token_id) in BINARY_INDEX_SETS.into_iter().zip(COLLECTION_IDS).zip(TOKEN_IDS) {
+            let collection_id = collection_id(B256::ZERO, condition_id, U256::from(index_set)).unwrap();
+            assert_eq!(collection_id, B256::from_str(collection).unwrap());
+            assert_eq!(position_id(collateral, collection_id), U256::from_str(token_id).unwrap());
+        }
+        let ids = binary_position_ids(collateral, condition_id).unwrap();
+        assert_eq!(ids.map(|id| id.to_string()), TOKEN_IDS.map(str::to_string));
+    }
+
+    #[test]
+    fn test_nested_collection_id_order_independent() {
+        let a = B256::repeat_byte(0x01);
+        let b = B256::repeat_byte(0x02);
+        let a_then_b = collection_id(collection_id(B256::ZERO, a, U256::from(1)).unwrap(), b, U256::from(2)).unwrap();
+        let b_then_a = collection_id(collection_id(B256::ZERO, b, U256::from(2)).unwrap(), a, U256::from(1)).unwrap();
+        assert_eq!(a_then_b, b_then_a);
+        // 4^3 + 3 has no square root mod P.
+        assert!(collection_id(B256::with_last_byte(4), a, U256::from(1)).is_err());
+    }
+}
@@ -17,9 +17,51 @@ pub enum Error {
     #[error("invalid signature type")]
     InvalidSignatureType,
 
+    #[error("invalid price, must be between 0 and 1")]
+    InvalidPrice,
+
+    #[error("invalid price, must be a multiple of the tick size {0}")]
+    InvalidTickSize(f64),
+
+    #[error("token not found in its market")]
+    TokenNotInMarket,
+
+    #[error("market has no complement token")]
+    NoComplementToken,
+
+    #[error("invalid execution parameters, slices, visible size and size must be positive")]
+    InvalidExecutionParams,
+
+    #[error("order rejected: {0}")]
+    OrderRejected(String),
+
+    #[error("child order closed unfilled")]
+    ChildOrderClosed,
+
+    #[error("order not canceled: {0}")]
+    OrderNotCanceled(String),
+
+    #[error("invalid order size")]
+    InvalidOrderSize,
+
+    #[error("invalid signer address")]
+    InvalidSignerAddress,
+
+    #[error("local signer error: {0}")]
+    LocalSignerError(#[from] alloy::signers::local::LocalSignerError),
+
     #[error("invalid proxy address")]
     InvalidProxyAddress,
 
+    #[error("proxy address {actual} does not match {expected} derived from the signer")]
+    ProxyAddressMismatch {
+        expected: alloy::primitives::Address,
+        actual:   alloy::primitives::Address,
+    },
+
+    #[error("no proxy wallet factory known for the chain")]
+    ProxyFactoryUnknown,
+
     #[error("environment variable error: {0}")]
     EnvVariableError(#[from] std::env::VarError),
 
@@ -29,6 +71,21 @@ pub enum Error {
     #[error("requires creds")]
     CredsRequired,
 
+    #[error("requires contracts, set a signer or use with_contracts")]
+    ContractsNotConfigured,
+
+    #[error("requires rpc url")]
+    RpcRequired,
+
+    #[error("invalid rpc url")]
+    InvalidRpcUrl,
+
+    #[error("requires server time, see with_server_time")]
+    ServerTimeRequired,
+
+    #[error("invalid order signature")]
+    InvalidOrderSignature,
+
     #[error("signing error: {0}")]
     SigningError(#[from] alloy::signers::Error),
 
@@ -47,8 +104,56 @@ pub enum Error {
     #[error("alloy primitive parse error: {0}")]
     ParseError(#[from] alloy::primitives::ruint::ParseError),
 
+    #[error("rpc error: {0}")]
+    RpcError(#[from] alloy::transports::TransportError),
+
+    #[error("contract error: {0}")]
+    ContractError(#[from] alloy::contract::Error),
+
+    #[error("pending transaction error: {0}")]
+    PendingTransactionError(#[from] alloy::providers::PendingTransactionError),
+
+    #[error("transaction reverted: {0}")]
+    TransactionReverted(alloy::primitives::TxHash),
+
+    #[error("on-chain transactions can only be sent for an EOA, not a proxy wallet")]
+    ProxyUnsupported,
+
+    #[error("invalid condition id, must be 32 byte hex")]
+    InvalidConditionId,
+
+    #[error("invalid token id, must be a decimal number")]
+    InvalidTokenId,
+
+    #[error("invalid order id, must be 32 byte hex")]
+    InvalidOrderId,
+
+    #[error("invalid order status")]
+    InvalidOrderStatus,
+
+    #[error("invalid collection id, not a point on the curve")]
+    InvalidCollectionId,
+
     #[error("API key deletion failed")]
     ApiKeyDeleteFailed,
+
+    #[error("io error: {0}")]
+    IoError(#[from] std::io::Error),
+
+    #[error("json error: {0}")]
+    JsonError(#[from] serde_json::Error),
+
+    #[error("toml error: {0}")]
+    TomlError(#[from] toml::de::Error),
+
+    #[error("unsupported file format, must be .json or .toml")]
+    UnsupportedFileFormat,
+
+    #[error("unable to encrypt creds for cache")]
+    CredsCacheEncryptFailed,
+
+    #[error("unable to decrypt cached creds, wrong signer or corrupted file")]
+    CredsCacheDecryptFailed,
     
 }
 
@@ -0,0 +1,603 @@
+use std::{str::FromStr, time::Duration};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use tokio::{sync::watch, task::JoinHandle, time::Instant};
+use crate::{client::{ClobClient, OrderArgs}, schema::{OrderId, OrderStatus}, Error, Result};
+
+// Execution of a parent order as a sequence of child orders, each at the parent's price.
+
+// How often a live child order is checked for fills.
+pub const DEFAULT_POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Algo {
+    // Equal slices posted every interval. The unfilled size of a slice is canceled at the end
+    // of its interval and carried into the next, the last slice's is left unfilled.
+    Twap { slices: u32, interval: Duration },
+    // At most `visible` size in hundredths resting at a time, replaced once filled.
+    Iceberg { visible: u32 },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub enum ExecutionStatus {
+    Running,
+    // The live child was canceled until resumed.
+    Paused,
+    Completed,
+    // The TWAP schedule ended before the parent filled.
+    Expired,
+    Canceled,
+    Failed(String),
+}
+
+// Sizes are in outcome tokens.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ChildOrder {
+    pub order_id: OrderId,
+    pub size:     f64,
+    pub filled:   f64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ExecutionReport {
+    pub status:      ExecutionStatus,
+    pub price:       f64,
+    pub requested:   f64,
+    pub filled:      f64,
+    pub children:    Vec<ChildOrder>,
+    pub started_at:  DateTime<Utc>,
+    pub finished_at: Option<DateTime<Utc>>,
+}
+
+impl ExecutionReport {
+    pub fn remaining(&self) -> f64 {
+        self.requested - self.filled
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Control {
+    Run,
+    Pause,
+    Cancel,
+}
+
+// Controls a running execution. Dropping the handle lets the execution run to its end.
+#[derive(Debug)]
+pub struct ExecutionHandle {
+    control: watch::Sender<Control>,
+    report:  watch::Receiver<ExecutionReport>,
+    task:    JoinHandle<ExecutionReport>,
+}
+
+impl ExecutionHandle {
+
+    // Cancels the live child, no new children are posted until resumed.
+    pub fn pause(&self) {
+        self.control.send_replace(Control::Pause);
+    }
+
+    pub fn resume(&self) {
+        self.control.send_replace(Control::Run);
+    }
+
+    // Cancels the live child and ends the execution.
+    pub fn cancel(&self) {
+        self.control.send_replace(Control::Cancel);
+    }
+
+    // Report of the execution so far.
+    pub fn progress(&self) -> ExecutionReport {
+        self.report.borrow().clone()
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.task.is_finished()
+    }
+
+    // Waits for the execution to end and returns its final report.
+    pub async fn report(self) -> ExecutionReport {
+        match self.task.await {
+            Ok(report) => report,
+            Err(e) => {
+                let mut report = self.report.borrow().clone();
+                report.status = ExecutionStatus::Failed(e.to_string());
+                report
+            }
+        }
+    }
+}
+
+// Starts executing the parent order in the background.
+// Sizes are in hundredths as with `OrderArgs`, the parent's type applies to every child.
+pub fn spawn(client: &ClobClient, parent: OrderArgs, algo: Algo, poll_interval: Duration) -> Result<ExecutionHandle> {
+    start(client.clone(), parent, algo, poll_interval)
+}
+
+fn start<C: OrderClient>(client: C, parent: OrderArgs, algo: Algo, poll_interval: Duration) -> Result<ExecutionHandle> {
+    let valid = match algo {
+        Algo::Twap { slices, .. }  => slices > 0,
+        Algo::Iceberg { visible } => visible > 0,
+    };
+    if !valid || parent.size == 0 {
+        return Err(Error::InvalidExecutionParams);
+    }
+
+    let (control_tx, control) = watch::channel(Control::Run);
+    let (report, report_rx) = watch::channel(ExecutionReport {
+        status:      ExecutionStatus::Running,
+        price:       parent.price as f64 / 1000.0,
+        requested:   parent.size as f64 / 100.0,
+        filled:      0.0,
+        children:    Vec::new(),
+        started_at:  Utc::now(),
+        finished_at: None,
+    });
+    let mut execution = Execution { client, parent, poll_interval, control, report, live: None };
+    let task = tokio::spawn(async move {
+        let status = match execution.run(algo).await {
+            Ok(status) => status,
+            Err(e) => {
+                // Best effort, the child may already be closed.
+                if let Err(e) = execution.cancel_child().await {
+                    tracing::warn!("failed to cancel child order: {}", e);
+                }
+                ExecutionStatus::Failed(e.to_string())
+            }
+        };
+        execution.report.send_modify(|report| {
+            report.status = status;
+            report.finished_at = Some(Utc::now());
+        });
+        execution.report.borrow().clone()
+    });
+    Ok(ExecutionHandle { control: control_tx, report: report_rx, task })
+}
+
+// Order calls of an execution, stubbed in tests.
+#[async_trait::async_trait]
+trait OrderClient: Send + Sync + 'static {
+    // Id of the posted order, an error if it was rejected.
+    async fn post(&self, args: OrderArgs) -> Result<OrderId>;
+    // Size matched in outcome tokens and status of the order.
+    async fn status(&self, order_id: OrderId) -> Result<(f64, OrderStatus)>;
+    // An error if the order was not canceled.
+    async fn cancel(&self, order_id: OrderId) -> Result<()>;
+}
+
+#[async_trait::async_trait]
+impl OrderClient for ClobClient {
+
+    async fn post(&self, args: OrderArgs) -> Result<OrderId> {
+        let resp = self.post_order(args).await?;
+        resp.order_id.filter(|_| resp.success).ok_or(Error::OrderRejected(resp.error_msg))
+    }
+
+    async fn status(&self, order_id: OrderId) -> Result<(f64, OrderStatus)> {
+        let order = self.get_order(&order_id.to_string()).await?;
+        let filled = f64::from_str(&order.size_matched).map_err(|_| Error::InvalidOrderSize)?;
+        Ok((filled, order.status))
+    }
+
+    async fn cancel(&self, order_id: OrderId) -> Result<()> {
+        let resp = self.cancel_order(&order_id.to_string()).await?;
+        match resp.not_canceled_reason(order_id) {
+            Some(reason) => Err(Error::OrderNotCanceled(reason.to_string())),
+            None         => Ok(()),
+        }
+    }
+}
+
+// Sizes of `slices` near equal slices summing to `size`, larger slices first.
+pub fn split_size(size: u32, slices: u32) -> Vec<u32> {
+    (0..slices).map(|i| size / slices + u32::from(i < size % slices)).collect()
+}
+
+// What ended waiting on a child.
+enum Flow {
+    // The child closed, filled or not.
+    Done,
+    Expired,
+    // Paused for the duration and resumed, the child was canceled.
+    Resumed(Duration),
+    Canceled,
+}
+
+struct Execution<C> {
+    client:        C,
+    parent:        OrderArgs,
+    poll_interval: Duration,
+    control:       watch::Receiver<Control>,
+    report:        watch::Sender<ExecutionReport>,
+    // Index of the live child in the report.
+    live:          Option<usize>,
+}
+
+impl<C: OrderClient> Execution<C> {
+
+    async fn run(&mut self, algo: Algo) -> Result<ExecutionStatus> {
+        match algo {
+            Algo::Twap { slices, interval } => {
+                let slices = split_size(self.parent.size, slices);
+                let mut deadline = Instant::now();
+                for i in 0..slices.len() {
+                    if self.remaining() == 0 {
+                        break;
+                    }
+                    deadline += interval;
+                    let mut post = true;
+                    loop {
+                        // Everything due by the end of this slice.
+                        let due = self.remaining().saturating_sub(slices[i + 1..].iter().sum());
+                        if post && due > 0 {
+                            self.post_child(due).await?;
+                        }
+                        post = false;
+                        match self.work(Some(deadline)).await? {
+                            Flow::Done => {}
+                            Flow::Expired => break,
+                            Flow::Resumed(paused) => {
+                                deadline += paused;
+                                post = true;
+                            }
+                            Flow::Canceled => return Ok(ExecutionStatus::Canceled),
+                        }
+                    }
+                    self.cancel_child().await?;
+                }
+            }
+            Algo::Iceberg { visible } => {
+                while self.remaining() > 0 {
+                    self.post_child(self.remaining().min(visible)).await?;
+                    match self.work(None).await? {
+                        Flow::Done if self.report.borrow().children.last().is_some_and(|child| child.filled == 0.0) => {
+                            return Err(Error::ChildOrderClosed);
+                        }
+                        Flow::Done | Flow::Expired | Flow::Resumed(_) => {}
+                        Flow::Canceled => return Ok(ExecutionStatus::Canceled),
+                    }
+                }
+            }
+        }
+        Ok(if self.remaining() == 0 { ExecutionStatus::Completed } else { ExecutionStatus::Expired })
+    }
+
+    // Unfilled size in hundredths.
+    fn remaining(&self) -> u32 {
+        let filled = (self.report.borrow().filled * 100.0).round() as u32;
+        self.parent.size.saturating_sub(filled)
+    }
+
+    async fn post_child(&mut self, size: u32) -> Result<()> {
+        let order_id = self.client.post(OrderArgs { size, ..self.parent.clone() }).await?;
+        self.report.send_modify(|report| {
+            report.children.push(ChildOrder { order_id, size: size as f64 / 100.0, filled: 0.0 });
+            self.live = Some(report.children.len() - 1);
+        });
+        Ok(())
+    }
+
+    // Records the live child's fills, true once it is closed.
+    // Delayed and unmatched orders were accepted and can still fill.
+    async fn poll_child(&mut self) -> Result<bool> {
+        let Some(index) = self.live else {
+            return Ok(true);
+        };
+        let order_id = self.report.borrow().children[index].order_id;
+        let (filled, status) = self.client.status(order_id).await?;
+        let done = matches!(status, OrderStatus::Matched | OrderStatus::Canceled | OrderStatus::CanceledMarketResolved);
+        self.report.send_modify(|report| {
+            report.filled += filled - report.children[index].filled;
+            report.children[index].filled = filled;
+        });
+        if done {
+            self.live = None;
+        }
+        Ok(done)
+    }
+
+    // Cancels the live child and records its fills up to the cancel.
+    // A child that fails to cancel and is still open stays live, so no other child is posted alongside it.
+    async fn cancel_child(&mut self) -> Result<()> {
+        let Some(index) = self.live else {
+            return Ok(());
+        };
+        let order_id = self.report.borrow().children[index].order_id;
+        let canceled = self.client.cancel(order_id).await;
+        loop {
+            match (self.poll_child().await?, &canceled) {
+                // Closed, possibly filled before the cancel reached it.
+                (true, _) => return Ok(()),
+                (false, Err(_)) => return canceled,
+                // Canceled but its status hasn't caught up yet.
+                (false, Ok(())) => tokio::time::sleep(self.poll_interval).await,
+            }
+        }
+    }
+
+    // Polls the live child, if any, until it closes or the deadline passes, handling pause and cancel.
+    async fn work(&mut self, deadline: Option<Instant>) -> Result<Flow> {
+        loop {
+            let now = Instant::now();
+            if deadline.is_some_and(|deadline| now >= deadline) {
+                return Ok(Flow::Expired);
+            }
+            if self.live.is_none() && deadline.is_none() {
+                return Ok(Flow::Done);
+            }
+
+            let wait = deadline.map_or(self.poll_interval, |deadline| (deadline - now).min(self.poll_interval));
+            match self.wait(wait).await {
+                Control::Run => {}
+                Control::Pause => {
+                    let paused_at = Instant::now();
+                    self.cancel_child().await?;
+                    self.report.send_modify(|report| report.status = ExecutionStatus::Paused);
+                    if !self.hold().await {
+                        return Ok(Flow::Canceled);
+                    }
+                    self.report.send_modify(|report| report.status = ExecutionStatus::Running);
+                    return Ok(Flow::Resumed(paused_at.elapsed()));
+                }
+                Control::Cancel => {
+                    self.cancel_child().await?;
+                    return Ok(Flow::Canceled);
+                }
+            }
+            if self.live.is_some() && self.poll_child().await? {
+                return Ok(Flow::Done);
+            }
+        }
+    }
+
+    // Waits for the duration, returning early on a pause or cancel.
+    async fn wait(&mut self, duration: Duration) -> Control {
+        let deadline = Instant::now() + duration;
+        loop {
+            match tokio::time::timeout_at(deadline, self.control.changed()).await {
+                Ok(Ok(())) if *self.control.borrow_and_update() == Control::Run => {}
+                Ok(Ok(())) | Err(_) => break,
+                // The handle was dropped, nothing can change the control anymore.
+                Ok(Err(_)) => {
+                    tokio::time::sleep_until(deadline).await;
+                    break;
+                }
+            }
+        }
+        *self.control.borrow()
+    }
+
+    // Waits while paused, false if canceled or the handle was dropped.
+    async fn hold(&mut self) -> bool {
+        while *self.control.borrow_and_update() == Control::Pause {
+            if self.control.changed().await.is_err() {
+                return false;
+            }
+        }
+        *self.control.borrow() == Control::Run
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+    use alloy::primitives::B256;
+    use tokio::{io::{AsyncReadExt, AsyncWriteExt}, net::TcpListener};
+    use crate::client::{ApiCreds, OrderType};
+    use super::*;
+
+    // Fills each order by `fill` per poll up to `depth` tokens, after `delayed` polls reporting it delayed.
+    // Cancels of open orders fail if `stuck`.
+    #[derive(Debug, Default)]
+    struct Stub {
+        fill:    f64,
+        depth:   f64,
+        delayed: usize,
+        stuck:   bool,
+        state:   Mutex<StubState>,
+    }
+
+    #[derive(Debug, Default)]
+    struct StubState {
+        // Size, matched, polls and status of each order, ids are indices plus one.
+        orders:   Vec<(f64, f64, usize, OrderStatus)>,
+        // Most orders open at once.
+        max_open: usize,
+    }
+
+    impl StubState {
+        fn open(&self) -> usize {
+            self.orders.iter().filter(|order| !matches!(order.3, OrderStatus::Matched | OrderStatus::Canceled)).count()
+        }
+    }
+
+    fn index(order_id: OrderId) -> usize {
+        order_id.0[31] as usize - 1
+    }
+
+    #[async_trait::async_trait]
+    impl OrderClient for Arc<Stub> {
+
+        async fn post(&self, args: OrderArgs) -> Result<OrderId> {
+            let mut state = self.state.lock().unwrap();
+            state.orders.push((args.size as f64 / 100.0, 0.0, 0, OrderStatus::Live));
+            state.max_open = state.max_open.max(state.open());
+            Ok(OrderId(B256::with_last_byte(state.orders.len() as u8)))
+        }
+
+        async fn status(&self, order_id: OrderId) -> Result<(f64, OrderStatus)> {
+            let mut state = self.state.lock().unwrap();
+            let (size, matched, polls, status) = &mut state.orders[index(order_id)];
+            *polls += 1;
+            if *status == OrderStatus::Live {
+                if *polls <= self.delayed {
+                    return Ok((*matched, OrderStatus::Delayed));
+                }
+                *matched = (*matched + self.fill).min(*size).min(self.depth);
+                if *matched == *size {
+                    *status = OrderStatus::Matched;
+                }
+            }
+            Ok((*matched, *status))
+        }
+
+        async fn cancel(&self, order_id: OrderId) -> Result<()> {
+            let mut state = self.state.lock().unwrap();
+            let order = &mut state.orders[index(order_id)];
+            if order.3 == OrderStatus::Live {
+                if self.stuck {
+                    return Err(Error::OrderNotCanceled("cancel failed".to_string()));
+                }
+                order.3 = OrderStatus::Canceled;
+            }
+            Ok(())
+        }
+    }
+
+    fn get_parent(size: u32) -> OrderArgs {
+        OrderArgs {
+            price:        500,
+            size,
+            buy:          true,
+            asset_id:     "1234".to_string(),
+            neg_risk:     Some(false),
+            fee_rate_bps: Some(0),
+            expiration:   None,
+            type_:        OrderType::GTC,
+        }
+    }
+
+    fn sizes(report: &ExecutionReport) -> Vec<f64> {
+        report.children.iter().map(|child| child.size).collect()
+    }
+
+    #[test]
+    fn test_split_size() {
+        assert_eq!(split_size(1000, 4), vec![250, 250, 250, 250]);
+        assert_eq!(split_size(1003, 4), vec![251, 251, 251, 250]);
+        assert_eq!(split_size(2, 4), vec![1, 1, 0, 0]);
+    }
+
+    #[tokio::test]
+    async fn test_spawn_validates() {
+        let client = ClobClient::new("http://localhost");
+        let twap = Algo::Twap { slices: 0, interval: Duration::from_secs(60) };
+        assert!(matches!(spawn(&client, get_parent(10000), twap, DEFAULT_POLL_INTERVAL), Err(Error::InvalidExecutionParams)));
+
+        // Fails to post the first child without a signer, the report says why.
+        let handle = spawn(&client, get_parent(10000), Algo::Iceberg { visible: 1000 }, DEFAULT_POLL_INTERVAL).unwrap();
+        let report = handle.report().await;
+        assert!(matches!(report.status, ExecutionStatus::Failed(_)));
+        assert!(report.children.is_empty());
+        assert_eq!(report.remaining(), 100.0);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_iceberg_replenishes() {
+        // Each slice is delayed for a poll and fills over three.
+        let stub = Arc::new(Stub { fill: 10.0, depth: f64::INFINITY, delayed: 1, ..Default::default() });
+        let handle = start(stub.clone(), get_parent(10000), Algo::Iceberg { visible: 3000 }, DEFAULT_POLL_INTERVAL).unwrap();
+        let report = handle.report().await;
+
+        assert_eq!(report.status, ExecutionStatus::Completed);
+        assert_eq!(sizes(&report), vec![30.0, 30.0, 30.0, 10.0]);
+        assert_eq!(report.filled, 100.0);
+        // The next slice is only posted once the last one filled, delayed or not.
+        assert_eq!(stub.state.lock().unwrap().max_open, 1);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_twap_carries_over() {
+        // Only 20 tokens fill per child.
+        let stub = Arc::new(Stub { fill: 20.0, depth: 20.0, ..Default::default() });
+        let twap = Algo::Twap { slices: 2, interval: Duration::from_secs(60) };
+        let report = start(stub.clone(), get_parent(10000), twap, DEFAULT_POLL_INTERVAL).unwrap().report().await;
+
+        // The second slice carries the first's unfilled 30.
+        assert_eq!(report.status, ExecutionStatus::Expired);
+        assert_eq!(sizes(&report), vec![50.0, 80.0]);
+        assert_eq!(report.filled, 40.0);
+        assert_eq!(report.remaining(), 60.0);
+        assert_eq!(stub.state.lock().unwrap().open(), 0);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_pause_resume_cancel() {
+        let stub = Arc::new(Stub { fill: 10.0, depth: 10.0, ..Default::default() });
+        let twap = Algo::Twap { slices: 1, interval: Duration::from_secs(60) };
+        let handle = start(stub.clone(), get_parent(10000), twap, DEFAULT_POLL_INTERVAL).unwrap();
+
+        tokio::time::sleep(Duration::from_secs(10)).await;
+        handle.pause();
+        tokio::time::sleep(Duration::from_secs(120)).await;
+        // The child was canceled, and pausing past the slice's end doesn't expire it.
+        let progress = handle.progress();
+        assert_eq!(progress.status, ExecutionStatus::Paused);
+        assert_eq!(progress.filled, 10.0);
+        assert_eq!(stub.state.lock().unwrap().open(), 0);
+
+        handle.resume();
+        tokio::time::sleep(Duration::from_secs(10)).await;
+        let progress = handle.progress();
+        assert_eq!(progress.status, ExecutionStatus::Running);
+        assert_eq!(sizes(&progress), vec![100.0, 90.0]);
+        assert_eq!(stub.state.lock().unwrap().open(), 1);
+
+        handle.cancel();
+        let report = handle.report().await;
+        assert_eq!(report.status, ExecutionStatus::Canceled);
+        assert_eq!(report.filled, 20.0);
+        assert_eq!(stub.state.lock().unwrap().open(), 0);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_cancel_fails() {
+        let stub = Arc::new(Stub { fill: 20.0, depth: 20.0, stuck: true, ..Default::default() });
+        let twap = Algo::Twap { slices: 2, interval: Duration::from_secs(60) };
+        let report = start(stub.clone(), get_parent(10000), twap, DEFAULT_POLL_INTERVAL).unwrap().report().await;
+
+        // The first slice's child is still open, so no second child is posted next to it.
+        assert!(matches!(report.status, ExecutionStatus::Failed(_)));
+        assert_eq!(sizes(&report), vec![50.0]);
+        assert_eq!(report.filled, 20.0);
+        assert_eq!(stub.state.lock().unwrap().open(), 1);
+    }
+
+    // Stand-in cancel endpoint, answers a single request with the body.
+    async fn serve_once(listener: TcpListener, resp_body: String) {
+        let (mut stream, _) = listener.accept().await.unwrap();
+        let mut buf = vec![0u8; 4096];
+        let mut len = 0;
+        // Read until the json body is complete.
+        while !buf[..len].ends_with(b"}") {
+            len += stream.read(&mut buf[len..]).await.unwrap();
+        }
+        let resp = format!(
+            "HTTP/1.1 200 OK\r\ncontent-type: application/json\r\ncontent-length: {}\r\n\r\n{}",
+            resp_body.len(),
+            resp_body,
+        );
+        stream.write_all(resp.as_bytes()).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_client_cancel_not_canceled() {
+        let order_id = OrderId(B256::repeat_byte(0xab));
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let resp_body = format!("{{\"canceled\":[],\"not_canceled\":{{\"{}\":\"matched\"}}}}", order_id);
+        let server = tokio::spawn(serve_once(listener, resp_body));
+
+        let client = ClobClient::new(&url)
+            .with_signer("0xac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80", 137)
+            .unwrap()
+            .with_creds(ApiCreds {
+                api_key:    "00000000-0000-0000-0000-000000000000".to_string(),
+                secret:     "AAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAA=".to_string(),
+                passphrase: "passphrase".to_string(),
+            });
+        let result = OrderClient::cancel(&client, order_id).await;
+        assert!(matches!(result, Err(Error::OrderNotCanceled(reason)) if reason == "matched"));
+        server.await.unwrap();
+    }
+}
@@ -0,0 +1,71 @@
+// Fees as charged by the CTF exchange, see `CalculatorHelper.calculateFee`.
+// The fee rate applies to the cheaper side of the trade, min(price, 1 - price), so
+// fees are symmetric between buying an outcome and selling its complement.
+
+const BPS: f64 = 10_000.0;
+
+// Fee of selling `size` outcome tokens, charged in USDC on the proceeds.
+pub fn sell_fee(fee_rate_bps: u32, price: f64, size: f64) -> f64 {
+    fee_rate_bps as f64 / BPS * price.min(1.0 - price) * size
+}
+
+// Fee of buying `size` outcome tokens, charged in outcome tokens on the proceeds.
+pub fn buy_fee(fee_rate_bps: u32, price: f64, size: f64) -> f64 {
+    if price <= 0.0 {
+        return 0.0;
+    }
+    fee_rate_bps as f64 / BPS * price.min(1.0 - price) * size / price
+}
+
+// Costs of an order if fully filled at its price.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct OrderCost {
+    // USDC paid for a buy or received for a sell, before fees.
+    pub notional: f64,
+    // In outcome tokens for a buy, USDC for a sell.
+    pub fee:      f64,
+    // Outcome tokens received for a buy or USDC received for a sell, after fees.
+    pub net:      f64,
+}
+
+// Price in [0, 1] and size in outcome tokens.
+pub fn order_cost(fee_rate_bps: u32, buy: bool, price: f64, size: f64) -> OrderCost {
+    let notional = price * size;
+    if buy {
+        let fee = buy_fee(fee_rate_bps, price, size);
+        OrderCost { notional, fee, net: size - fee }
+    } else {
+        let fee = sell_fee(fee_rate_bps, price, size);
+        OrderCost { notional, fee, net: notional - fee }
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    // Float equality up to rounding error, shared by the tests of other float math.
+    pub(crate) fn assert_close(a: f64, b: f64) {
+        assert!((a - b).abs() < 1e-9, "{a} != {b}");
+    }
+
+    #[test]
+    fn test_fees() {
+        // 2% at even odds.
+        assert_close(sell_fee(200, 0.5, 100.0), 1.0);
+        assert_close(buy_fee(200, 0.5, 100.0), 2.0);
+        // Charged on the cheaper side.
+        assert_close(sell_fee(200, 0.8, 100.0), 0.4);
+        assert_close(sell_fee(200, 0.2, 100.0), 0.4);
+        assert_close(buy_fee(200, 0.2, 100.0), 2.0);
+        assert_eq!(buy_fee(200, 0.0, 100.0), 0.0);
+
+        let cost = order_cost(200, true, 0.25, 100.0);
+        assert_close(cost.notional, 25.0);
+        assert_close(cost.net, 98.0);
+        let cost = order_cost(200, false, 0.25, 100.0);
+        assert_close(cost.fee, 0.5);
+        assert_close(cost.net, 24.5);
+        assert_eq!(order_cost(0, false, 0.25, 100.0).net, 25.0);
+    }
+}
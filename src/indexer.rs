@@ -0,0 +1,277 @@
+use std::collections::HashMap;
+use alloy::{
+    primitives::{Address, TxHash, B256, U256},
+    providers::{Provider, RootProvider},
+    rpc::types::{Filter, Log},
+    sol_types::SolEvent,
+    transports::http::{Client, Http},
+};
+use crate::{abi::ICTFExchange, contracts::ContractConfig, Error, Result};
+
+// Blocks per `eth_getLogs` request, public RPCs commonly cap the range of a single request.
+pub const DEFAULT_CHUNK_SIZE: u64 = 2000;
+
+// Where an event was emitted.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct LogMeta {
+    pub exchange:     Address,
+    pub block_number: u64,
+    pub tx_hash:      TxHash,
+    pub log_index:    u64,
+}
+
+// An order filled on chain, amounts are in base units. Asset id zero is collateral,
+// so an order buying outcome tokens has a zero maker asset id.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Fill {
+    pub order_hash:          B256,
+    pub maker:               Address,
+    pub taker:               Address,
+    pub maker_asset_id:      U256,
+    pub taker_asset_id:      U256,
+    pub maker_amount_filled: U256,
+    pub taker_amount_filled: U256,
+    pub fee:                 U256,
+    pub meta:                LogMeta,
+}
+
+impl Fill {
+
+    // Outcome token traded.
+    pub fn token_id(&self) -> U256 {
+        if self.is_buy() { self.taker_asset_id } else { self.maker_asset_id }
+    }
+
+    // Whether the order bought outcome tokens.
+    pub fn is_buy(&self) -> bool {
+        self.maker_asset_id.is_zero()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ExchangeEvent {
+    OrderFilled(Fill),
+    OrdersMatched {
+        taker_order_hash:    B256,
+        taker_order_maker:   Address,
+        maker_asset_id:      U256,
+        taker_asset_id:      U256,
+        maker_amount_filled: U256,
+        taker_amount_filled: U256,
+        meta:                LogMeta,
+    },
+    OrderCancelled {
+        order_hash: B256,
+        meta:       LogMeta,
+    },
+    TokenRegistered {
+        token0:       U256,
+        token1:       U256,
+        condition_id: B256,
+        meta:         LogMeta,
+    },
+}
+
+// Decodes a CTF exchange log, none if it is not an exchange event.
+pub fn decode_log(log: &Log) -> Option<ExchangeEvent> {
+    let meta = LogMeta {
+        exchange:     log.address(),
+        block_number: log.block_number.unwrap_or_default(),
+        tx_hash:      log.transaction_hash.unwrap_or_default(),
+        log_index:    log.log_index.unwrap_or_default(),
+    };
+
+    match *log.topic0()? {
+        ICTFExchange::OrderFilled::SIGNATURE_HASH => {
+            let event = ICTFExchange::OrderFilled::decode_log(&log.inner, true).ok()?.data;
+            Some(ExchangeEvent::OrderFilled(Fill {
+                order_hash:          event.orderHash,
+                maker:               event.maker,
+                taker:               event.taker,
+                maker_asset_id:      event.makerAssetId,
+                taker_asset_id:      event.takerAssetId,
+                maker_amount_filled: event.makerAmountFilled,
+                taker_amount_filled: event.takerAmountFilled,
+                fee:                 event.fee,
+                meta,
+            }))
+        }
+        ICTFExchange::OrdersMatched::SIGNATURE_HASH => {
+            let event = ICTFExchange::OrdersMatched::decode_log(&log.inner, true).ok()?.data;
+            Some(ExchangeEvent::OrdersMatched {
+                taker_order_hash:    event.takerOrderHash,
+                taker_order_maker:   event.takerOrderMaker,
+                maker_asset_id:      event.makerAssetId,
+                taker_asset_id:      event.takerAssetId,
+                maker_amount_filled: event.makerAmountFilled,
+                taker_amount_filled: event.takerAmountFilled,
+                meta,
+            })
+        }
+        ICTFExchange::OrderCancelled::SIGNATURE_HASH => {
+            let event = ICTFExchange::OrderCancelled::decode_log(&log.inner, true).ok()?.data;
+            Some(ExchangeEvent::OrderCancelled { order_hash: event.orderHash, meta })
+        }
+        ICTFExchange::TokenRegistered::SIGNATURE_HASH => {
+            let event = ICTFExchange::TokenRegistered::decode_log(&log.inner, true).ok()?.data;
+            Some(ExchangeEvent::TokenRegistered {
+                token0:       event.token0,
+                token1:       event.token1,
+                condition_id: event.conditionId,
+                meta,
+            })
+        }
+        _ => None,
+    }
+}
+
+// Scans the regular and neg risk exchanges for events, independently of the CLOB API.
+#[derive(Debug, Clone)]
+pub struct FillIndexer {
+    provider:   RootProvider<Http<Client>>,
+    exchanges:  Vec<Address>,
+    maker:      Option<Address>,
+    chunk_size: u64,
+}
+
+impl FillIndexer {
+
+    pub fn new(rpc_url: &str, contracts: &ContractConfig) -> Result<Self> {
+        Ok(Self {
+            provider:   RootProvider::new_http(rpc_url.parse().map_err(|_| Error::InvalidRpcUrl)?),
+            exchanges:  vec![contracts.exchange, contracts.neg_risk_exchange],
+            maker:      None,
+            chunk_size: DEFAULT_CHUNK_SIZE,
+        })
+    }
+
+    // Only index fills of orders made by this address.
+    pub fn with_maker(mut self, maker: Address) -> Self {
+        self.maker = Some(maker);
+        self
+    }
+
+    pub fn with_chunk_size(mut self, chunk_size: u64) -> Self {
+        self.chunk_size = chunk_size.max(1);
+        self
+    }
+
+    pub async fn latest_block(&self) -> Result<u64> {
+        Ok(self.provider.get_block_number().await?)
+    }
+
+    async fn scan(&self, from_block: u64, to_block: u64, signatures: Vec<B256>, maker: Option<Address>) -> Result<Vec<ExchangeEvent>> {
+        let mut events = Vec::new();
+        let mut start = from_block;
+        while start <= to_block {
+            let end = to_block.min(start.saturating_add(self.chunk_size - 1));
+            let mut filter = Filter::new()
+                .address(self.exchanges.clone())
+                .event_signature(signatures.clone())
+                .from_block(start)
+                .to_block(end);
+            if let Some(maker) = maker {
+                filter = filter.topic2(maker.into_word());
+            }
+            events.extend(self.provider.get_logs(&filter).await?.iter().filter_map(decode_log));
+            if end == u64::MAX {
+                break;
+            }
+            start = end + 1;
+        }
+        Ok(events)
+    }
+
+    // All exchange events in the inclusive block range, regardless of maker.
+    pub async fn events(&self, from_block: u64, to_block: u64) -> Result<Vec<ExchangeEvent>> {
+        let signatures = vec![
+            ICTFExchange::OrderFilled::SIGNATURE_HASH,
+            ICTFExchange::OrdersMatched::SIGNATURE_HASH,
+            ICTFExchange::OrderCancelled::SIGNATURE_HASH,
+            ICTFExchange::TokenRegistered::SIGNATURE_HASH,
+        ];
+        self.scan(from_block, to_block, signatures, None).await
+    }
+
+    // Fills in the inclusive block range keyed by order hash, in chain order.
+    // Both sides of a match emit `OrderFilled`, so these include orders filled as maker and as taker.
+    pub async fn fills(&self, from_block: u64, to_block: u64) -> Result<HashMap<B256, Vec<Fill>>> {
+        let events = self.scan(from_block, to_block, vec![ICTFExchange::OrderFilled::SIGNATURE_HASH], self.maker).await?;
+        let mut fills: HashMap<B256, Vec<Fill>> = HashMap::new();
+        for event in events {
+            if let ExchangeEvent::OrderFilled(fill) = event {
+                fills.entry(fill.order_hash).or_default().push(fill);
+            }
+        }
+        Ok(fills)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy::{primitives::LogData, rpc::types::Log};
+    use super::*;
+
+    fn get_log(exchange: Address, data: LogData) -> Log {
+        Log {
+            inner:            alloy::primitives::Log { address: exchange, data },
+            block_number:     Some(100),
+            transaction_hash: Some(TxHash::repeat_byte(0x11)),
+            log_index:        Some(3),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_decode_order_filled() {
+        let exchange = Address::with_last_byte(0xee);
+        let event = ICTFExchange::OrderFilled {
+            orderHash:         B256::repeat_byte(0x01),
+            maker:             Address::with_last_byte(1),
+            taker:             Address::with_last_byte(2),
+            makerAssetId:      U256::ZERO,
+            takerAssetId:      U256::from(1234),
+            makerAmountFilled: U256::from(50_000_000),
+            takerAmountFilled: U256::from(100_000_000),
+            fee:               U256::from(10),
+        };
+        let Some(ExchangeEvent::OrderFilled(fill)) = decode_log(&get_log(exchange, event.encode_log_data())) else {
+            panic!("expected an order filled event");
+        };
+        assert_eq!(fill.order_hash, B256::repeat_byte(0x01));
+        assert_eq!(fill.maker, Address::with_last_byte(1));
+        assert_eq!(fill.taker_amount_filled, U256::from(100_000_000));
+        assert_eq!(fill.token_id(), U256::from(1234));
+        assert!(fill.is_buy());
+        assert_eq!(fill.meta, LogMeta {
+            exchange,
+            block_number: 100,
+            tx_hash:      TxHash::repeat_byte(0x11),
+            log_index:    3,
+        });
+    }
+
+    #[test]
+    fn test_decode_other_events() {
+        let exchange = Address::with_last_byte(0xee);
+        let cancelled = ICTFExchange::OrderCancelled { orderHash: B256::repeat_byte(0x02) };
+        assert!(matches!(
+            decode_log(&get_log(exchange, cancelled.encode_log_data())),
+            Some(ExchangeEvent::OrderCancelled { order_hash, .. }) if order_hash == B256::repeat_byte(0x02)
+        ));
+
+        let registered = ICTFExchange::TokenRegistered {
+            token0:      U256::from(1),
+            token1:      U256::from(2),
+            conditionId: B256::repeat_byte(0x03),
+        };
+        assert!(matches!(
+            decode_log(&get_log(exchange, registered.encode_log_data())),
+            Some(ExchangeEvent::TokenRegistered { token1, .. }) if token1 == U256::from(2)
+        ));
+
+        // Not an exchange event.
+        let unknown = LogData::new_unchecked(vec![B256::repeat_byte(0xff)], Default::default());
+        assert!(decode_log(&get_log(exchange, unknown)).is_none());
+    }
+}
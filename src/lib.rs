@@ -1,8 +1,22 @@
 
 mod error;
 mod auth;
-mod contracts;
+mod abi;
+pub mod arbitrage;
+pub mod catalog;
 pub mod client;
+pub mod clock;
+pub mod contracts;
+pub mod ctf;
+pub mod execution;
+pub mod fees;
+pub mod indexer;
+pub mod quoter;
+pub mod rewards;
+pub mod salt;
 pub mod schema;
+pub mod signer;
+pub mod wallet;
 
-pub use error::{Error, Result};
\ No newline at end of file
+pub use error::{Error, Result};
+pub use auth::{order_hash, order_hash_with_contracts, order_struct_hash, recover_order_signer, verify_order};
\ No newline at end of file
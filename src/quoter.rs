@@ -0,0 +1,350 @@
+use serde::{Deserialize, Serialize};
+use crate::{
+    client::{ClobClient, OrderArgs, OrderType},
+    rewards::{self, Quote},
+    schema::{OrderId, Orderbook, TokenId, END_CURSOR},
+    Error,
+    Result,
+};
+
+// Two sided quoting of a token around its midpoint, within the market's reward spread.
+// Asks are posted as bids of the complement token at one minus the price, so quoting needs
+// no inventory of the token and inventory is the net position, tokens held less complement tokens held.
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct QuoterConfig {
+    // Orders per side.
+    pub levels:            usize,
+    // Size of each order in outcome tokens, raised to the market's min size so that it scores.
+    pub size:              f64,
+    // Distance of the first level from the midpoint as a fraction of the max spread, at least a tick.
+    pub spread:            f64,
+    // Ticks between levels.
+    pub level_ticks:       u32,
+    // Requote once the midpoint moves further than this from the one quoted around.
+    pub requote_threshold: f64,
+    // Net position at which the side adding to it stops quoting.
+    pub max_inventory:     f64,
+    // How far quotes shift away from the side of the position at max inventory, as a fraction of the max spread.
+    pub skew:              f64,
+}
+
+impl Default for QuoterConfig {
+    fn default() -> Self {
+        Self {
+            levels:            1,
+            size:              100.0,
+            spread:            0.5,
+            level_ticks:       1,
+            requote_threshold: 0.005,
+            max_inventory:     1000.0,
+            skew:              0.5,
+        }
+    }
+}
+
+// Quotes of the token around the midpoint, best first on each side.
+// Prices are multiples of the tick size, bids at or below and asks at or above the midpoint.
+// If the market has rewards, levels as far as the max spread from the midpoint or further are dropped as they don't score.
+pub fn ladder(config: &QuoterConfig, midpoint: f64, tick_size: f64, max_spread: f64, min_size: f64, inventory: f64, rewards: bool) -> Vec<Quote> {
+    let ratio = if config.max_inventory > 0.0 { (inventory / config.max_inventory).clamp(-1.0, 1.0) } else { 0.0 };
+    // Long positions lower both sides, making asks more and bids less likely to fill.
+    let center = midpoint - ratio * config.skew * max_spread;
+    let half_spread = (config.spread * max_spread).max(tick_size);
+    let size = config.size.max(min_size);
+    // Against float error in prices already on the tick.
+    let epsilon = 1e-9;
+    let scores = |price: f64| !rewards || (price - midpoint).abs() < max_spread - epsilon;
+
+    let mut quotes = Vec::with_capacity(config.levels * 2);
+    for level in 0..config.levels {
+        let offset = (level as u32 * config.level_ticks) as f64 * tick_size;
+        let bid = ((center - half_spread - offset) / tick_size + epsilon).floor() * tick_size;
+        let ask = ((center + half_spread + offset) / tick_size - epsilon).ceil() * tick_size;
+        if ratio < 1.0 && bid >= tick_size - epsilon && bid <= midpoint + epsilon && scores(bid) {
+            quotes.push(Quote { buy: true, price: bid, size });
+        }
+        if ratio > -1.0 && ask <= 1.0 - tick_size + epsilon && ask >= midpoint - epsilon && scores(ask) {
+            quotes.push(Quote { buy: false, price: ask, size });
+        }
+    }
+    quotes
+}
+
+// Orders of the quotes, asks are bids of the complement at one minus the price.
+fn quote_orders(quotes: &[Quote], token_id: TokenId, complement: TokenId, neg_risk: bool, fee_rate_bps: u32) -> Vec<OrderArgs> {
+    quotes.iter().map(|quote| {
+        let price = (quote.price * 1000.0).round() as u32;
+        let (asset_id, price) = if quote.buy { (token_id, price) } else { (complement, 1000 - price) };
+        OrderArgs {
+            price,
+            size:         (quote.size * 100.0).round() as u32,
+            buy:          true,
+            asset_id:     asset_id.to_string(),
+            neg_risk:     Some(neg_risk),
+            fee_rate_bps: Some(fee_rate_bps),
+            expiration:   None,
+            type_:        OrderType::GTC,
+        }
+    }).collect()
+}
+
+// Resting orders split by whether the ladder still wants them, and the wanted orders not resting.
+#[derive(Debug, Default)]
+struct Diff {
+    keep:  Vec<(OrderId, OrderArgs)>,
+    stale: Vec<(OrderId, OrderArgs)>,
+    post:  Vec<OrderArgs>,
+}
+
+fn diff(resting: Vec<(OrderId, OrderArgs)>, wanted: Vec<OrderArgs>) -> Diff {
+    let (keep, stale): (Vec<_>, Vec<_>) = resting
+        .into_iter()
+        .partition(|(_, args)| wanted.iter().any(|wanted| same_order(wanted, args)));
+    let post = wanted
+        .into_iter()
+        .filter(|wanted| !keep.iter().any(|(_, args)| same_order(args, wanted)))
+        .collect();
+    Diff { keep, stale, post }
+}
+
+// Orders posted and canceled by an update.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct QuoteUpdate {
+    pub posted:   usize,
+    pub canceled: usize,
+    // Posts the API rejected, retried on the next update.
+    pub rejected: usize,
+}
+
+// Maintains a ladder of orders of a binary market's token. Each update only cancels the resting
+// orders that left the ladder, in one request, and posts the missing ones.
+#[derive(Debug)]
+pub struct Quoter {
+    client:    ClobClient,
+    token_id:  TokenId,
+    config:    QuoterConfig,
+    inventory: f64,
+    // Midpoint the ladder is around, moved once the midpoint moves past the requote threshold.
+    anchor:    Option<f64>,
+    resting:   Vec<(OrderId, OrderArgs)>,
+}
+
+impl Quoter {
+
+    pub fn new(client: ClobClient, token_id: TokenId, config: QuoterConfig) -> Self {
+        Self { client, token_id, config, inventory: 0.0, anchor: None, resting: Vec::new() }
+    }
+
+    pub fn token_id(&self) -> TokenId {
+        self.token_id
+    }
+
+    pub fn config(&self) -> &QuoterConfig {
+        &self.config
+    }
+
+    // Takes effect on the next update.
+    pub fn set_config(&mut self, config: QuoterConfig) {
+        self.config = config;
+    }
+
+    pub fn inventory(&self) -> f64 {
+        self.inventory
+    }
+
+    // Net position, e.g. from balances or fills, skews the next update.
+    pub fn set_inventory(&mut self, inventory: f64) {
+        self.inventory = inventory;
+    }
+
+    // Orders posted by the quoter and believed to be resting.
+    pub fn resting(&self) -> &[(OrderId, OrderArgs)] {
+        &self.resting
+    }
+
+    // Fetches the book and the market's open orders, forgets filled or canceled orders and updates.
+    pub async fn step(&mut self) -> Result<QuoteUpdate> {
+        let metadata = self.client.get_market_metadata(self.token_id).await?;
+        let book = self.client.get_market_book(&self.token_id.to_string()).await?;
+
+        let market = metadata.market.condition_id.to_string();
+        let mut open = Vec::new();
+        let mut cursor = None;
+        loop {
+            let page = self.client.get_active_orders(cursor.as_deref(), None, Some(&market)).await?;
+            open.extend(page.data.into_iter().map(|order| order.id));
+            if page.next_cursor == END_CURSOR || page.next_cursor.is_empty() {
+                break;
+            }
+            cursor = Some(page.next_cursor);
+        }
+        self.resting.retain(|(id, _)| open.contains(id));
+
+        self.update(&book).await
+    }
+
+    // Requotes against the book. Quotes are pulled if the book has no midpoint.
+    pub async fn update(&mut self, book: &Orderbook) -> Result<QuoteUpdate> {
+        let metadata = self.client.get_market_metadata(self.token_id).await?;
+        let complement = metadata.market.complement(self.token_id).ok_or(Error::NoComplementToken)?.token_id;
+        let rewards = &metadata.market.rewards;
+        let min_size = rewards.min_size as f64;
+
+        let Some(midpoint) = rewards::adjusted_midpoint(book, min_size) else {
+            self.anchor = None;
+            return Ok(QuoteUpdate { canceled: self.pull().await?, ..Default::default() });
+        };
+        let anchor = match self.anchor {
+            Some(anchor) if (midpoint - anchor).abs() <= self.config.requote_threshold => anchor,
+            _ => midpoint,
+        };
+        self.anchor = Some(anchor);
+
+        let quotes = ladder(&self.config, anchor, metadata.tick_size, rewards.max_spread / 100.0, min_size, self.inventory, rewards.enabled());
+        let wanted = quote_orders(&quotes, self.token_id, complement, metadata.neg_risk, metadata.fee_rate_bps());
+
+        let mut update = QuoteUpdate::default();
+        let Diff { keep, stale, post } = diff(std::mem::take(&mut self.resting), wanted);
+        self.resting = keep;
+        update.canceled = self.cancel(stale).await?;
+
+        for args in post {
+            match self.client.post_order(args.clone()).await {
+                Ok(resp) if resp.success && resp.order_id.is_some() => {
+                    self.resting.push((resp.order_id.unwrap(), args));
+                    update.posted += 1;
+                }
+                Ok(resp) => {
+                    tracing::warn!("quote rejected: {}", resp.error_msg);
+                    update.rejected += 1;
+                }
+                Err(e @ Error::ApiError { .. }) => {
+                    tracing::warn!("quote rejected: {}", e);
+                    update.rejected += 1;
+                }
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(update)
+    }
+
+    // Cancels all resting quotes, returns the number canceled.
+    pub async fn pull(&mut self) -> Result<usize> {
+        let resting = std::mem::take(&mut self.resting);
+        self.cancel(resting).await
+    }
+
+    // Cancels the orders in one request, returns the number canceled. Orders that fail to cancel
+    // stay resting, so they are canceled again by the next update or forgotten by `step` once closed.
+    async fn cancel(&mut self, orders: Vec<(OrderId, OrderArgs)>) -> Result<usize> {
+        if orders.is_empty() {
+            return Ok(0);
+        }
+        let ids: Vec<String> = orders.iter().map(|(id, _)| id.to_string()).collect();
+        let resp = match self.client.cancel_orders(&ids).await {
+            Ok(resp) => resp,
+            Err(e) => {
+                self.resting.extend(orders);
+                return Err(e);
+            }
+        };
+        for (id, reason) in &resp.not_canceled {
+            tracing::warn!("quote {} not canceled: {}", id, reason);
+        }
+        self.resting.extend(orders.into_iter().filter(|(id, _)| resp.not_canceled_reason(*id).is_some()));
+        Ok(resp.canceled.len())
+    }
+}
+
+fn same_order(a: &OrderArgs, b: &OrderArgs) -> bool {
+    a.asset_id == b.asset_id && a.buy == b.buy && a.price == b.price && a.size == b.size
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy::primitives::{B256, U256};
+    use super::*;
+
+    fn id(id: u64) -> TokenId {
+        TokenId(U256::from(id))
+    }
+
+    fn order_id(id: u8) -> OrderId {
+        OrderId(B256::with_last_byte(id))
+    }
+
+    fn prices(quotes: &[Quote], buy: bool) -> Vec<f64> {
+        quotes.iter().filter(|quote| quote.buy == buy).map(|quote| (quote.price * 1000.0).round() / 1000.0).collect()
+    }
+
+    #[test]
+    fn test_ladder() {
+        let config = QuoterConfig { levels: 2, size: 10.0, ..Default::default() };
+        // Half of a 3 cent max spread, rounded away from the midpoint onto the tick.
+        // The second level is at the max spread, so it only quotes without rewards.
+        let quotes = ladder(&config, 0.5, 0.01, 0.03, 50.0, 0.0, true);
+        assert_eq!(prices(&quotes, true), vec![0.48]);
+        assert_eq!(prices(&quotes, false), vec![0.52]);
+        assert!(quotes.iter().all(|quote| quote.size == 50.0));
+        let quotes = ladder(&config, 0.5, 0.01, 0.03, 50.0, 0.0, false);
+        assert_eq!(prices(&quotes, true), vec![0.48, 0.47]);
+        assert_eq!(prices(&quotes, false), vec![0.52, 0.53]);
+
+        // Near the edge of the price range.
+        let quotes = ladder(&config, 0.015, 0.01, 0.03, 0.0, 0.0, true);
+        assert_eq!(prices(&quotes, true), Vec::<f64>::new());
+        assert_eq!(prices(&quotes, false), vec![0.03, 0.04]);
+    }
+
+    #[test]
+    fn test_ladder_inventory_skew() {
+        let config = QuoterConfig { max_inventory: 100.0, skew: 1.0, ..Default::default() };
+        // Long half the max inventory shifts both sides down by half the max spread,
+        // the bid lands at the max spread and no longer scores.
+        let quotes = ladder(&config, 0.5, 0.01, 0.04, 0.0, 50.0, false);
+        assert_eq!(prices(&quotes, true), vec![0.46]);
+        assert_eq!(prices(&quotes, false), vec![0.5]);
+        let quotes = ladder(&config, 0.5, 0.01, 0.04, 0.0, 50.0, true);
+        assert_eq!(prices(&quotes, true), Vec::<f64>::new());
+        assert_eq!(prices(&quotes, false), vec![0.5]);
+
+        // At max inventory only the side reducing it quotes.
+        let quotes = ladder(&config, 0.5, 0.01, 0.04, 0.0, 100.0, false);
+        assert_eq!(prices(&quotes, true), Vec::<f64>::new());
+        let quotes = ladder(&config, 0.5, 0.01, 0.04, 0.0, -100.0, false);
+        assert_eq!(prices(&quotes, false), Vec::<f64>::new());
+    }
+
+    #[test]
+    fn test_quote_orders() {
+        let quotes = [Quote { buy: true, price: 0.48, size: 50.0 }, Quote { buy: false, price: 0.52, size: 50.0 }];
+        let orders = quote_orders(&quotes, id(1), id(2), true, 100);
+        assert_eq!(orders[0].asset_id, "1");
+        assert_eq!(orders[0].price, 480);
+        // The ask is a bid of the complement at one minus the price.
+        assert_eq!(orders[1].asset_id, "2");
+        assert_eq!(orders[1].price, 480);
+        assert!(orders.iter().all(|order| order.buy && order.size == 5000));
+        assert!(orders.iter().all(|order| order.neg_risk == Some(true) && order.fee_rate_bps == Some(100)));
+    }
+
+    #[test]
+    fn test_diff() {
+        let order = |asset_id: u64, price: u32| quote_orders(&[Quote { buy: true, price: price as f64 / 1000.0, size: 50.0 }], id(asset_id), id(0), false, 0).remove(0);
+        let resting = vec![(order_id(1), order(1, 480)), (order_id(2), order(2, 480)), (order_id(3), order(1, 470))];
+        // The ladder moved up a tick on the bid side.
+        let wanted = vec![order(1, 490), order(1, 480), order(2, 480)];
+
+        let Diff { keep, stale, post } = diff(resting, wanted);
+        assert_eq!(keep.iter().map(|(id, _)| *id).collect::<Vec<_>>(), vec![order_id(1), order_id(2)]);
+        assert_eq!(stale.iter().map(|(id, _)| *id).collect::<Vec<_>>(), vec![order_id(3)]);
+        assert_eq!(post.len(), 1);
+        assert!(same_order(&post[0], &order(1, 490)));
+
+        // Same price on another token, or another size, is another order.
+        assert!(!same_order(&order(1, 480), &order(2, 480)));
+        assert!(!same_order(&order(1, 480), &OrderArgs { size: 100, ..order(1, 480) }));
+        assert!(same_order(&order(1, 480), &OrderArgs { fee_rate_bps: None, ..order(1, 480) }));
+    }
+}
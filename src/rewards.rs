@@ -0,0 +1,142 @@
+use crate::schema::{Orderbook, Rewards};
+
+// Local estimate of liquidity reward scores, following the Polymarket liquidity rewards program.
+// Prices and spreads are in units of implied probability, sizes in outcome tokens.
+
+// Single sided liquidity scores a third of two sided liquidity.
+pub const SINGLE_SIDED_FACTOR: f64 = 3.0;
+// Outside this midpoint range only two sided liquidity scores.
+pub const SINGLE_SIDED_MIN_MIDPOINT: f64 = 0.10;
+pub const SINGLE_SIDED_MAX_MIDPOINT: f64 = 0.90;
+
+// S = ((v - s) / v)^2 * size, for an order `spread` from the midpoint and max spread v.
+pub fn order_score(max_spread: f64, spread: f64, size: f64) -> f64 {
+    if max_spread <= 0.0 || spread > max_spread {
+        return 0.0;
+    }
+    ((max_spread - spread) / max_spread).powi(2) * size
+}
+
+// Q_min of the scores of either side of the market.
+// Bids of a token are on the same side as asks of its complement.
+pub fn two_sided_score(q_one: f64, q_two: f64, midpoint: f64) -> f64 {
+    if (SINGLE_SIDED_MIN_MIDPOINT..=SINGLE_SIDED_MAX_MIDPOINT).contains(&midpoint) {
+        q_one.min(q_two).max(q_one.max(q_two) / SINGLE_SIDED_FACTOR)
+    } else {
+        q_one.min(q_two)
+    }
+}
+
+// Midpoint of the best levels of at least `min_size`, so small orders can't move it.
+pub fn adjusted_midpoint(book: &Orderbook, min_size: f64) -> Option<f64> {
+    let (best_bid, _) = book.bid_levels().into_iter().find(|&(_, size)| size >= min_size)?;
+    let (best_ask, _) = book.ask_levels().into_iter().find(|&(_, size)| size >= min_size)?;
+    Some((best_bid + best_ask) / 2.0)
+}
+
+// A proposed order of the book's token.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Quote {
+    pub buy:   bool,
+    pub price: f64,
+    pub size:  f64,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScoreEstimate {
+    pub midpoint: f64,
+    // Score of each quote, zero if it is too small or too far from the midpoint.
+    pub scores:   Vec<f64>,
+    // Scores of the bids and asks.
+    pub q_one:    f64,
+    pub q_two:    f64,
+    pub q_min:    f64,
+    // Estimated share of the market's rewards against the liquidity resting in the book.
+    pub share:    f64,
+}
+
+impl ScoreEstimate {
+    pub fn is_scoring(&self) -> bool {
+        self.q_min > 0.0
+    }
+}
+
+// Scores the quotes would earn against the book, none if the book has no midpoint.
+// Rewards' max spread is in cents. The quotes' own sizes don't move the midpoint.
+pub fn estimate_quotes(rewards: &Rewards, book: &Orderbook, quotes: &[Quote]) -> Option<ScoreEstimate> {
+    let min_size = rewards.min_size as f64;
+    let max_spread = rewards.max_spread / 100.0;
+    let midpoint = adjusted_midpoint(book, min_size)?;
+    let score = |price: f64, size: f64| {
+        if size < min_size { 0.0 } else { order_score(max_spread, (price - midpoint).abs(), size) }
+    };
+
+    let scores: Vec<f64> = quotes.iter().map(|quote| score(quote.price, quote.size)).collect();
+    let side = |buy: bool| quotes.iter().zip(&scores).filter(|(quote, _)| quote.buy == buy).map(|(_, s)| s).sum::<f64>();
+    let (q_one, q_two) = (side(true), side(false));
+    let q_min = two_sided_score(q_one, q_two, midpoint);
+
+    let book_side = |levels: Vec<(f64, f64)>| levels.into_iter().map(|(price, size)| score(price, size)).sum::<f64>();
+    let book_q_min = two_sided_score(book_side(book.bid_levels()), book_side(book.ask_levels()), midpoint);
+    let share = if q_min > 0.0 { q_min / (q_min + book_q_min) } else { 0.0 };
+
+    Some(ScoreEstimate { midpoint, scores, q_one, q_two, q_min, share })
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+    use crate::fees::tests::assert_close;
+    use super::*;
+
+    fn get_book(bids: &[(&str, &str)], asks: &[(&str, &str)]) -> Orderbook {
+        let levels = |levels: &[(&str, &str)]| levels.iter().map(|(price, size)| json!({ "price": price, "size": size })).collect::<Vec<_>>();
+        serde_json::from_value(json!({
+            "market":    "0x0101010101010101010101010101010101010101010101010101010101010101",
+            "asset_id":  "1",
+            "hash":      "",
+            "timestamp": "0",
+            "bids":      levels(bids),
+            "asks":      levels(asks),
+        })).unwrap()
+    }
+
+    fn get_rewards() -> Rewards {
+        Rewards { min_size: 20, max_spread: 3.0, rates: None }
+    }
+
+    #[test]
+    fn test_order_score() {
+        assert_close(order_score(0.03, 0.0, 100.0), 100.0);
+        assert_close(order_score(0.03, 0.015, 100.0), 25.0);
+        assert_eq!(order_score(0.03, 0.031, 100.0), 0.0);
+        // Single sided liquidity scores a third near even odds, nothing at the extremes.
+        assert_close(two_sided_score(90.0, 0.0, 0.5), 30.0);
+        assert_eq!(two_sided_score(90.0, 0.0, 0.95), 0.0);
+        assert_close(two_sided_score(90.0, 60.0, 0.5), 60.0);
+    }
+
+    #[test]
+    fn test_estimate_quotes() {
+        // The small bid at 0.49 doesn't move the midpoint off 0.5.
+        let book = get_book(&[("0.47", "100"), ("0.49", "5")], &[("0.53", "100")]);
+        assert_close(adjusted_midpoint(&book, 20.0).unwrap(), 0.5);
+
+        let quotes = [
+            Quote { buy: true,  price: 0.49, size: 90.0 },
+            Quote { buy: false, price: 0.51, size: 90.0 },
+            // Too small and too wide.
+            Quote { buy: true,  price: 0.49, size: 10.0 },
+            Quote { buy: false, price: 0.54, size: 90.0 },
+        ];
+        let estimate = estimate_quotes(&get_rewards(), &book, &quotes).unwrap();
+        assert_close(estimate.scores[0], 40.0);
+        assert_eq!(&estimate.scores[2..], &[0.0, 0.0]);
+        assert_close(estimate.q_min, 40.0);
+        assert!(estimate.is_scoring());
+        // Resting levels 0.03 away don't score, the small bid is below the min size.
+        assert_close(estimate.share, 1.0);
+
+        assert!(estimate_quotes(&get_rewards(), &get_book(&[], &[("0.53", "100")]), &quotes).is_none());
+    }
+}
@@ -0,0 +1,63 @@
+use std::{fmt, sync::Mutex};
+use alloy::primitives::U256;
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+// Source of order salts, which make otherwise identical orders unique.
+pub trait SaltGenerator: Send + Sync + fmt::Debug {
+    fn next_salt(&self) -> U256;
+}
+
+// Random salt from the thread rng.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RandomSalt;
+
+impl SaltGenerator for RandomSalt {
+    fn next_salt(&self) -> U256 {
+        U256::from(rand::thread_rng().gen::<u32>())
+    }
+}
+
+// Reproducible sequence of salts from a seed.
+#[derive(Debug)]
+pub struct SeededSalt {
+    rng: Mutex<StdRng>,
+}
+
+impl SeededSalt {
+    pub fn new(seed: u64) -> Self {
+        Self { rng: Mutex::new(StdRng::seed_from_u64(seed)) }
+    }
+}
+
+impl SaltGenerator for SeededSalt {
+    fn next_salt(&self) -> U256 {
+        // Lock only poisoned if a holder panicked, the rng state is still valid.
+        let mut rng = self.rng.lock().unwrap_or_else(|e| e.into_inner());
+        U256::from(rng.gen::<u32>())
+    }
+}
+
+// Always returns the same salt.
+#[derive(Debug, Clone, Copy)]
+pub struct FixedSalt(pub U256);
+
+impl SaltGenerator for FixedSalt {
+    fn next_salt(&self) -> U256 {
+        self.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_seeded_salt_reproducible() {
+        let a = SeededSalt::new(7);
+        let b = SeededSalt::new(7);
+        let salts: Vec<U256> = (0..4).map(|_| a.next_salt()).collect();
+        assert_eq!(salts, (0..4).map(|_| b.next_salt()).collect::<Vec<_>>());
+        assert_ne!(salts[0], salts[1]);
+        assert_ne!(salts[0], SeededSalt::new(8).next_salt());
+    }
+}
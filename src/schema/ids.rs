@@ -0,0 +1,102 @@
+use std::{fmt, str::FromStr};
+use alloy::primitives::{B256, U256};
+use crate::Error;
+use super::wire::serde_via_str;
+
+// ERC-1155 id of an outcome token, a decimal string on the wire.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct TokenId(pub U256);
+
+// CTF condition id, also the id of a market. A 32 byte hex string on the wire.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct ConditionId(pub B256);
+
+// Id of an order, its EIP-712 hash. A 32 byte hex string on the wire.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct OrderId(pub B256);
+
+impl fmt::Display for TokenId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl FromStr for TokenId {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        U256::from_str_radix(s, 10).map(Self).map_err(|_| Error::InvalidTokenId)
+    }
+}
+
+impl From<U256> for TokenId {
+    fn from(id: U256) -> Self {
+        Self(id)
+    }
+}
+
+impl fmt::Display for ConditionId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:#x}", self.0)
+    }
+}
+
+impl FromStr for ConditionId {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        B256::from_str(s).map(Self).map_err(|_| Error::InvalidConditionId)
+    }
+}
+
+impl From<B256> for ConditionId {
+    fn from(id: B256) -> Self {
+        Self(id)
+    }
+}
+
+impl fmt::Display for OrderId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:#x}", self.0)
+    }
+}
+
+impl FromStr for OrderId {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        B256::from_str(s).map(Self).map_err(|_| Error::InvalidOrderId)
+    }
+}
+
+impl From<B256> for OrderId {
+    fn from(id: B256) -> Self {
+        Self(id)
+    }
+}
+
+serde_via_str!(TokenId);
+serde_via_str!(ConditionId);
+serde_via_str!(OrderId);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ids_round_trip() {
+        let token_id = "21742633143463906290569050155826241533067272736897614950488156847949938836455";
+        let json = format!("\"{}\"", token_id);
+        let id: TokenId = serde_json::from_str(&json).unwrap();
+        assert_eq!(id.to_string(), token_id);
+        assert_eq!(serde_json::to_string(&id).unwrap(), json);
+
+        let condition_id = "0xdd22472e552920b8438158ea7238bfadfa4f736aa4cee91a6b86c39ead110917";
+        let json = format!("\"{}\"", condition_id);
+        let id: ConditionId = serde_json::from_str(&json).unwrap();
+        assert_eq!(serde_json::to_string(&id).unwrap(), json);
+
+        assert!(serde_json::from_str::<TokenId>("\"0x01\"").is_err());
+        assert!(serde_json::from_str::<OrderId>("\"0x01\"").is_err());
+    }
+}
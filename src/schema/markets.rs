@@ -1,7 +1,16 @@
-use serde::Deserialize;
+use chrono::{DateTime, Utc};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use super::{wire::{iso_datetime_opt, millis_str}, ConditionId, TokenId};
+
+// Cursor of the first page.
+pub const START_CURSOR: &str = "MA==";
+// Cursor of the last page.
+pub const END_CURSOR: &str = "LTE=";
 
 // Where T is the type of the market.
-#[derive(Debug, Deserialize)]
+// Markets that fail to deserialize are collected in `errors` rather than failing the page.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(from = "RawMarkets", bound(deserialize = "T: DeserializeOwned"))]
 pub struct Markets<T> {
     pub limit:       u32,
     pub count:       u32,
@@ -9,22 +18,69 @@ pub struct Markets<T> {
     // 'LTE=' means the end and '' means the beginning.
     pub next_cursor: String,
     pub data:        Vec<T>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub errors:      Vec<MarketError>,
+}
+
+// A market of a page that failed to deserialize.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct MarketError {
+    // Position in the page.
+    pub index:        usize,
+    pub condition_id: Option<String>,
+    pub error:        String,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Deserialize)]
+struct RawMarkets {
+    limit:       u32,
+    count:       u32,
+    next_cursor: String,
+    data:        Vec<serde_json::Value>,
+    // Only present when reading back a serialized page.
+    #[serde(default)]
+    errors:      Vec<MarketError>,
+}
+
+impl<T: DeserializeOwned> From<RawMarkets> for Markets<T> {
+    fn from(raw: RawMarkets) -> Self {
+        let mut data = Vec::with_capacity(raw.data.len());
+        let mut errors = raw.errors;
+        for (index, value) in raw.data.into_iter().enumerate() {
+            let condition_id = value.get("condition_id").and_then(|id| id.as_str()).map(str::to_string);
+            match serde_json::from_value(value) {
+                Ok(market) => data.push(market),
+                Err(e) => {
+                    tracing::warn!("skipping malformed market {:?}: {}", condition_id, e);
+                    errors.push(MarketError { index, condition_id, error: e.to_string() });
+                }
+            }
+        }
+        Self {
+            limit:       raw.limit,
+            count:       raw.count,
+            next_cursor: raw.next_cursor,
+            data,
+            errors,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
 pub struct Market {
-    pub accepting_order_timestamp: Option<String>,
+    #[serde(default, with = "iso_datetime_opt")]
+    pub accepting_order_timestamp: Option<DateTime<Utc>>,
     pub accepting_orders:          bool,
     pub active:                    bool,
     pub closed:                    bool,
     pub archived:                  bool,
     // Id of market which is also the CTF condition ID.
-    pub condition_id:              String,
+    pub condition_id:              ConditionId,
     // Question id of market which is also the CTF question ID which is used to derive the `condition_id`.
     pub question_id:               String,
     pub is_50_50_outcome:          bool,
     pub enable_order_book:         bool,
-    pub tokens:                    [Token; 2],
+    pub tokens:                    Vec<Token>,
     pub rewards:                   Rewards,
     pub maker_base_fee:            u32,
     pub taker_base_fee:            u32,
@@ -32,11 +88,12 @@ pub struct Market {
     // Minimum tick size in units of implied probability (max price resolution).
     pub minimum_tick_size:         f64,
     pub description:               String,
-    // ISO string of market end date.
-    // TODO: convert to datetime
-    pub end_date_iso:              Option<String>,
-    // ISO string of game start time which is used to trigger delay.
-    pub game_start_time:           Option<String>,
+    // Market end date.
+    #[serde(default, with = "iso_datetime_opt")]
+    pub end_date_iso:              Option<DateTime<Utc>>,
+    // Game start time which is used to trigger delay.
+    #[serde(default, with = "iso_datetime_opt")]
+    pub game_start_time:           Option<DateTime<Utc>>,
     pub question:                  String,
     pub market_slug:               String,
     // Seconds of match delay for in-game trading.
@@ -54,25 +111,69 @@ pub struct Market {
     pub tags:                      Option<Vec<String>>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct SimplifiedMarketResponse {
     pub accepting_orders: bool,
     pub active:           bool,
     pub archived:         bool,
-    pub condition_id:     String,
+    pub condition_id:     ConditionId,
     pub rewards:          Rewards,
-    pub tokens:           [Token; 2],
+    pub tokens:           Vec<Token>,
+}
+
+impl Market {
+
+    pub fn token(&self, token_id: TokenId) -> Option<&Token> {
+        self.tokens.iter().find(|token| token.token_id == token_id)
+    }
+
+    // Other token of a binary market, none for multi-outcome markets.
+    pub fn complement(&self, token_id: TokenId) -> Option<&Token> {
+        complement(&self.tokens, token_id)
+    }
+
+    // Token of the outcome, case insensitive.
+    pub fn outcome(&self, name: &str) -> Option<&Token> {
+        outcome(&self.tokens, name)
+    }
+}
+
+impl SimplifiedMarketResponse {
+
+    pub fn token(&self, token_id: TokenId) -> Option<&Token> {
+        self.tokens.iter().find(|token| token.token_id == token_id)
+    }
+
+    pub fn complement(&self, token_id: TokenId) -> Option<&Token> {
+        complement(&self.tokens, token_id)
+    }
+
+    pub fn outcome(&self, name: &str) -> Option<&Token> {
+        outcome(&self.tokens, name)
+    }
+}
+
+fn complement(tokens: &[Token], token_id: TokenId) -> Option<&Token> {
+    match tokens {
+        [a, b] if a.token_id == token_id => Some(b),
+        [a, b] if b.token_id == token_id => Some(a),
+        _ => None,
+    }
+}
+
+fn outcome<'a>(tokens: &'a [Token], name: &str) -> Option<&'a Token> {
+    tokens.iter().find(|token| token.outcome.eq_ignore_ascii_case(name))
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct Token {
-    pub token_id: String,
+    pub token_id: TokenId,
     pub outcome:  String,
     pub price:    f64,
     pub winner:   bool,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct Rewards {
     // Minimum size of an order to score.
     pub min_size:   u32,
@@ -81,24 +182,172 @@ pub struct Rewards {
     pub rates:      Option<Vec<Rates>>,
 }
 
-#[derive(Debug, Deserialize)]
+impl Rewards {
+
+    // Whether orders in the market earn liquidity rewards.
+    pub fn enabled(&self) -> bool {
+        self.rates.as_ref().is_some_and(|rates| rates.iter().any(|rate| rate.rewards_daily_rate > 0.0))
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
 pub struct Rates {
     pub asset_address:      String,
     pub rewards_daily_rate: f64,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct Orderbook {
-    pub market:    String,
-    pub asset_id:  String,
+    pub market:    ConditionId,
+    pub asset_id:  TokenId,
     pub hash:      String,
-    pub timestamp: String,
+    #[serde(with = "millis_str")]
+    pub timestamp: DateTime<Utc>,
     pub bids:      Vec<OrderSummary>,
     pub asks:      Vec<OrderSummary>,
 }
 
-#[derive(Debug, Deserialize)]
+impl Orderbook {
+
+    // Bids as (price, size) best first, levels that fail to parse or are empty are skipped.
+    pub fn bid_levels(&self) -> Vec<(f64, f64)> {
+        let mut levels = parse_levels(&self.bids);
+        levels.sort_by(|a, b| b.0.total_cmp(&a.0));
+        levels
+    }
+
+    // Asks as (price, size) best first.
+    pub fn ask_levels(&self) -> Vec<(f64, f64)> {
+        let mut levels = parse_levels(&self.asks);
+        levels.sort_by(|a, b| a.0.total_cmp(&b.0));
+        levels
+    }
+}
+
+fn parse_levels(levels: &[OrderSummary]) -> Vec<(f64, f64)> {
+    levels
+        .iter()
+        .filter_map(|level| Some((level.price.parse().ok()?, level.size.parse().ok()?)))
+        .filter(|&(_, size): &(f64, f64)| size > 0.0)
+        .collect()
+}
+
+#[derive(Debug, Serialize, Deserialize)]
 pub struct OrderSummary {
     pub price: String,
     pub size:  String
-}
\ No newline at end of file
+}
+
+// Base fee rate in bps of a token, from `/fee-rate`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FeeRate {
+    pub base_fee: u32,
+}
+#[cfg(test)]
+pub(crate) mod tests {
+    use alloy::primitives::{B256, U256};
+    use serde_json::json;
+    use super::*;
+
+    // Neg risk market of tokens 1 and 2 with a 0.01 tick size.
+    pub(crate) fn get_market() -> Market {
+        serde_json::from_value(json!({
+            "accepting_order_timestamp": null,
+            "accepting_orders":          true,
+            "active":                    true,
+            "closed":                    false,
+            "archived":                  false,
+            "condition_id":              ConditionId(B256::repeat_byte(1)),
+            "question_id":               "",
+            "is_50_50_outcome":          false,
+            "enable_order_book":         true,
+            "tokens":                    [
+                { "token_id": "1", "outcome": "Yes", "price": 0.5, "winner": false },
+                { "token_id": "2", "outcome": "No",  "price": 0.5, "winner": false },
+            ],
+            "rewards":                   { "min_size": 0, "max_spread": 0.0, "rates": null },
+            "maker_base_fee":            0,
+            "taker_base_fee":            200,
+            "minimum_order_size":        5,
+            "minimum_tick_size":         0.01,
+            "description":               "",
+            "end_date_iso":              null,
+            "game_start_time":           null,
+            "question":                  "Will it rain?",
+            "market_slug":               "will-it-rain",
+            "seconds_delay":             0,
+            "icon":                      "",
+            "image":                     "",
+            "fpmm":                      "",
+            "neg_risk":                  true,
+            "neg_risk_market_id":        "",
+            "neg_risk_request_id":       "",
+            "notifications_enabled":     false,
+            "tags":                      null,
+        })).unwrap()
+    }
+
+    fn simplified_market(condition_id: u8, outcomes: &[&str]) -> serde_json::Value {
+        json!({
+            "accepting_orders": true,
+            "active":           true,
+            "archived":         false,
+            "condition_id":     ConditionId(B256::with_last_byte(condition_id)),
+            "rewards":          { "min_size": 0, "max_spread": 0.0, "rates": null },
+            "tokens":           outcomes.iter().enumerate().map(|(i, outcome)| json!({
+                "token_id": i.to_string(),
+                "outcome":  outcome,
+                "price":    0.5,
+                "winner":   false,
+            })).collect::<Vec<_>>(),
+        })
+    }
+
+    fn id(id: u64) -> TokenId {
+        TokenId(U256::from(id))
+    }
+
+    #[test]
+    fn test_markets_page_tolerant() {
+        let mut malformed = simplified_market(3, &["Yes", "No"]);
+        malformed["active"] = json!("yes");
+        let page = json!({
+            "limit":       3,
+            "count":       3,
+            "next_cursor": "LTE=",
+            "data":        [
+                simplified_market(1, &["Yes", "No"]),
+                simplified_market(2, &["Red", "Green", "Blue"]),
+                malformed,
+            ],
+        });
+
+        let markets: Markets<SimplifiedMarketResponse> = serde_json::from_value(page).unwrap();
+        assert_eq!(markets.data.len(), 2);
+        assert_eq!(markets.data[1].tokens.len(), 3);
+        assert_eq!(markets.errors.len(), 1);
+        assert_eq!(markets.errors[0].index, 2);
+        assert_eq!(markets.errors[0].condition_id, Some(ConditionId(B256::with_last_byte(3)).to_string()));
+    }
+
+    #[test]
+    fn test_complement_and_outcome() {
+        let binary: SimplifiedMarketResponse = serde_json::from_value(simplified_market(1, &["Yes", "No"])).unwrap();
+        assert_eq!(binary.complement(id(0)).unwrap().outcome, "No");
+        assert_eq!(binary.complement(id(1)).unwrap().outcome, "Yes");
+        assert!(binary.complement(id(2)).is_none());
+        assert_eq!(binary.outcome("yes").unwrap().token_id, id(0));
+
+        let multi: SimplifiedMarketResponse = serde_json::from_value(simplified_market(2, &["Red", "Green", "Blue"])).unwrap();
+        assert!(multi.complement(id(0)).is_none());
+        assert_eq!(multi.outcome("Blue").unwrap().token_id, id(2));
+        assert!(multi.outcome("Purple").is_none());
+    }
+
+    #[test]
+    fn test_fee_rate() {
+        let fee_rate: FeeRate = serde_json::from_value(json!({ "base_fee": 200 })).unwrap();
+        assert_eq!(fee_rate.base_fee, 200);
+        assert!(serde_json::from_value::<FeeRate>(json!({ "fee_rate_bps": 200 })).is_err());
+    }
+}
@@ -1,7 +1,16 @@
 
+mod ids;
 mod markets;
 mod orders;
+mod rewards;
 mod user;
+mod wire;
 
+pub use ids::*;
 pub use markets::*;
 pub use orders::*;
+pub use rewards::*;
+pub use user::*;
+
+#[cfg(test)]
+pub(crate) use markets::tests::get_market;
@@ -1,13 +1,21 @@
-use std::collections::HashMap;
-use alloy::{sol, primitives::U256};
-use serde::{Serializer, Serialize, Deserialize};
+use std::{collections::HashMap, fmt, str::FromStr};
+use alloy::sol;
+use chrono::{DateTime, Utc};
+use serde::{Serialize, Deserialize};
+use crate::Error;
+use super::{
+    wire::{empty_as_none, seconds_str_opt, serde_via_str, side_as_str, u256_as_dec_str, u256_as_u128},
+    ConditionId,
+    OrderId,
+    TokenId,
+};
 
 // Intermediate order struct.
 sol! {
-    #[derive(Debug, Serialize)]
+    #[derive(Debug, Serialize, Deserialize)]
     struct Order {
         
-        #[serde(serialize_with = "serialize_u256_as_u128")]
+        #[serde(with = "u256_as_u128")]
         uint256 salt;
 
         address maker;
@@ -16,106 +24,300 @@ sol! {
         
         address taker;
         
-        #[serde(serialize_with = "serialize_u256_as_dec_str")]
+        #[serde(with = "u256_as_dec_str")]
         uint256 tokenId;
         
-        #[serde(serialize_with = "serialize_u256_as_dec_str")]
+        #[serde(with = "u256_as_dec_str")]
         uint256 makerAmount;
         
-        #[serde(serialize_with = "serialize_u256_as_dec_str")]
+        #[serde(with = "u256_as_dec_str")]
         uint256 takerAmount;
         
-        #[serde(serialize_with = "serialize_u256_as_dec_str")]
+        #[serde(with = "u256_as_dec_str")]
         uint256 expiration;
         
-        #[serde(serialize_with = "serialize_u256_as_dec_str")]
+        #[serde(with = "u256_as_dec_str")]
         uint256 nonce;
         
-        #[serde(serialize_with = "serialize_u256_as_dec_str")]
+        #[serde(with = "u256_as_dec_str")]
         uint256 feeRateBps;
         
-        #[serde(serialize_with = "serialize_side")]
+        #[serde(with = "side_as_str")]
         uint8   side;
  
         uint8   signatureType;
     }
 }
 
-// Add this serializer function
-fn serialize_u256_as_dec_str<S>(value: &U256, serializer: S) -> Result<S::Ok, S::Error>
-where
-    S: Serializer,
-{
-    serializer.serialize_str(&value.to_string())
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum Side {
+    Buy,
+    Sell,
 }
 
-fn serialize_u256_as_u128<S>(value: &U256, serializer: S) -> Result<S::Ok, S::Error>
-where
-    S: Serializer,
-{
-    serializer.serialize_u128(value.to::<u128>())
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum OrderType {
+    /* 
+    A 'Fill-Or-Kill' order is an market order to buy 
+    shares that must be executed immediately in its entirety; 
+    otherwise, the entire order will be cancelled. 
+    */
+    FOK,
+    /* 
+    A 'Good-Til-Cancelled' order is a limit order that is 
+    active until it is fulfilled or cancelled. 
+    */
+    GTC,
+    /* 
+    A 'Good-Til-Day' order is a type of order that is 
+    active until its specified date (UTC seconds timestamp), 
+    unless it has already been fulfilled or cancelled. 
+    */
+    GTD,
 }
 
-fn serialize_side<S>(value: &u8, serializer: S) -> Result<S::Ok, S::Error>
-where
-    S: Serializer,
-{
-    match value {
-        0 => serializer.serialize_str("BUY"),
-        1 => serializer.serialize_str("SELL"),
-        _ => Err(serde::ser::Error::custom("invalid side, must be 0 for buy, 1 for sell"))
+impl fmt::Display for Side {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Side::Buy  => "BUY",
+            Side::Sell => "SELL",
+        })
     }
 }
 
+impl fmt::Display for OrderType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self, f)
+    }
+}
+
+// The API is inconsistent in case, e.g. "live" when posting and "LIVE" for open orders,
+// so parsing ignores case and serializing is uppercase.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum OrderStatus {
+    // Resting on the book.
+    Live,
+    // Matched with resting orders.
+    Matched,
+    // Marketable but subject to a matching delay.
+    Delayed,
+    // Marketable but failed to delay, placement was successful.
+    Unmatched,
+    Canceled,
+    // Canceled because the market resolved.
+    CanceledMarketResolved,
+}
+
+impl fmt::Display for OrderStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            OrderStatus::Live                   => "LIVE",
+            OrderStatus::Matched                => "MATCHED",
+            OrderStatus::Delayed                => "DELAYED",
+            OrderStatus::Unmatched              => "UNMATCHED",
+            OrderStatus::Canceled               => "CANCELED",
+            OrderStatus::CanceledMarketResolved => "CANCELED_MARKET_RESOLVED",
+        })
+    }
+}
+
+impl FromStr for OrderStatus {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s.to_ascii_uppercase().as_str() {
+            "LIVE"                     => OrderStatus::Live,
+            "MATCHED"                  => OrderStatus::Matched,
+            "DELAYED"                  => OrderStatus::Delayed,
+            "UNMATCHED"                => OrderStatus::Unmatched,
+            "CANCELED"                 => OrderStatus::Canceled,
+            "CANCELED_MARKET_RESOLVED" => OrderStatus::CanceledMarketResolved,
+            _                          => return Err(Error::InvalidOrderStatus),
+        })
+    }
+}
+
+serde_via_str!(OrderStatus);
 
 // Signed intermediate order struct.
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct SignedOrder {
     #[serde(flatten)]
     pub order:     Order,
     pub signature: String,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct OrderResponse {
     pub success:            bool,
     pub error_msg:          String,
-    #[serde(rename = "orderID")]
-    pub order_id:           String,
+    // None if the order was rejected.
+    #[serde(rename = "orderID", with = "empty_as_none")]
+    pub order_id:           Option<OrderId>,
     pub transaction_hashes: Option<Vec<String>>,
-    pub status:             String,
+    #[serde(with = "empty_as_none")]
+    pub status:             Option<OrderStatus>,
     pub making_amount:      String,
     pub taking_amount:      String,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct CancelResponse {
-    pub canceled:     Vec<String>,
+    pub canceled:     Vec<OrderId>,
+    // Reason per order, keyed by the id as echoed back which need not be a valid order id.
     pub not_canceled: HashMap<String, String>,
 }
 
-#[derive(Debug, Deserialize)]
+impl CancelResponse {
+    // Why the order wasn't canceled, none if it wasn't reported as not canceled.
+    pub fn not_canceled_reason(&self, id: OrderId) -> Option<&str> {
+        self.not_canceled
+            .iter()
+            .find(|(key, _)| key.parse::<OrderId>().ok() == Some(id))
+            .map(|(_, reason)| reason.as_str())
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
 pub struct OpenOrders {
     pub data:        Vec<OpenOrder>,
     pub next_cursor: String,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct OpenOrder {
-    pub asset_id:         String,
+    pub asset_id:         TokenId,
     pub associate_trades: Vec<String>,
-    pub created_at:       u64,
-    pub expiration:       String,
-    pub id:               String,
+    #[serde(with = "chrono::serde::ts_seconds")]
+    pub created_at:       DateTime<Utc>,
+    // None if the order does not expire.
+    #[serde(with = "seconds_str_opt")]
+    pub expiration:       Option<DateTime<Utc>>,
+    pub id:               OrderId,
     pub maker_address:    String,
-    pub market:           String,
-    pub order_type:       String,
+    pub market:           ConditionId,
+    pub order_type:       OrderType,
     pub original_size:    String,
     pub outcome:          String,
     pub owner:            String,
     pub price:            String,
-    pub side:             String,
+    pub side:             Side,
     pub size_matched:     String,
-    pub status:           String,
-}
\ No newline at end of file
+    pub status:           OrderStatus,
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy::primitives::{Address, U256};
+    use serde_json::json;
+    use super::*;
+
+    #[test]
+    fn test_open_order_round_trip() {
+        let wire = json!({
+            "asset_id":         "1234",
+            "associate_trades": [],
+            "created_at":       1700000000,
+            "expiration":       "0",
+            "id":               format!("0x{}", "ab".repeat(32)),
+            "maker_address":    "0x0000000000000000000000000000000000000001",
+            "market":           format!("0x{}", "cd".repeat(32)),
+            "order_type":       "GTC",
+            "original_size":    "10",
+            "outcome":          "Yes",
+            "owner":            "00000000-0000-0000-0000-000000000000",
+            "price":            "0.5",
+            "side":             "BUY",
+            "size_matched":     "0",
+            "status":           "LIVE",
+        });
+        let order: OpenOrder = serde_json::from_value(wire.clone()).unwrap();
+        assert_eq!(order.asset_id, TokenId(U256::from(1234)));
+        assert_eq!(order.created_at.timestamp(), 1700000000);
+        assert_eq!(order.expiration, None);
+        assert_eq!(order.order_type, OrderType::GTC);
+        assert_eq!(order.side, Side::Buy);
+        assert_eq!(order.status, OrderStatus::Live);
+        assert_eq!(serde_json::to_value(&order).unwrap(), wire);
+    }
+
+    #[test]
+    fn test_order_response_status() {
+        let resp: OrderResponse = serde_json::from_value(json!({
+            "success":           true,
+            "errorMsg":          "",
+            "orderID":           format!("0x{}", "ab".repeat(32)),
+            "transactionHashes": null,
+            "status":            "matched",
+            "makingAmount":      "5",
+            "takingAmount":      "10",
+        })).unwrap();
+        assert_eq!(resp.status, Some(OrderStatus::Matched));
+
+        let rejected: OrderResponse = serde_json::from_value(json!({
+            "success":           false,
+            "errorMsg":          "not enough balance / allowance",
+            "orderID":           "",
+            "transactionHashes": null,
+            "status":            "",
+            "makingAmount":      "",
+            "takingAmount":      "",
+        })).unwrap();
+        assert_eq!(rejected.order_id, None);
+        assert_eq!(rejected.status, None);
+    }
+
+    #[test]
+    fn test_signed_order_round_trip() {
+        let signed_order = SignedOrder {
+            order: Order {
+                salt:          U256::from(479249096354_u64),
+                maker:         Address::with_last_byte(1),
+                signer:        Address::with_last_byte(1),
+                taker:         Address::ZERO,
+                tokenId:       U256::from(1234),
+                makerAmount:   U256::from(100),
+                takerAmount:   U256::from(200),
+                expiration:    U256::ZERO,
+                nonce:         U256::from(3),
+                feeRateBps:    U256::ZERO,
+                side:          1,
+                signatureType: 0,
+            },
+            signature: format!("0x{}", "ab".repeat(65)),
+        };
+        let json = serde_json::to_value(&signed_order).unwrap();
+        assert_eq!(json["salt"], json!(479249096354_u64));
+        assert_eq!(json["tokenId"], json!("1234"));
+        assert_eq!(json["side"], json!("SELL"));
+
+        let decoded: SignedOrder = serde_json::from_value(json.clone()).unwrap();
+        assert_eq!(decoded.order.tokenId, U256::from(1234));
+        assert_eq!(decoded.order.side, 1);
+        assert_eq!(serde_json::to_value(&decoded).unwrap(), json);
+
+        // A salt too large for the wire format is an error, not a panic.
+        let mut order = decoded.order;
+        order.salt = U256::MAX;
+        assert!(serde_json::to_value(&order).is_err());
+    }
+
+    #[test]
+    fn test_cancel_response_bad_key() {
+        let id = format!("0x{}", "AB".repeat(32));
+        let order_id: OrderId = id.parse().unwrap();
+        let wire = json!({
+            "canceled":     [],
+            "not_canceled": {
+                id:          "order can't be found - already canceled or matched",
+                "not-an-id": "order not found",
+            },
+        });
+        let resp: CancelResponse = serde_json::from_value(wire).unwrap();
+        assert_eq!(resp.not_canceled.len(), 2);
+        assert_eq!(resp.not_canceled_reason(order_id), Some("order can't be found - already canceled or matched"));
+        assert_eq!(resp.not_canceled_reason(OrderId::default()), None);
+    }
+}
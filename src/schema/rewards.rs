@@ -0,0 +1,61 @@
+use alloy::primitives::Address;
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use super::ConditionId;
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct UserEarnings {
+    pub data:        Vec<UserEarning>,
+    pub next_cursor: String,
+}
+
+// Rewards earned by the maker in a market on a day.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct UserEarning {
+    pub date:          NaiveDate,
+    pub condition_id:  ConditionId,
+    // Token the rewards are paid in.
+    pub asset_address: Address,
+    pub maker_address: Address,
+    pub earnings:      f64,
+    pub asset_rate:    f64,
+}
+
+// Rewards earned by the maker across all markets on a day, per reward token.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TotalUserEarning {
+    pub date:          NaiveDate,
+    pub asset_address: Address,
+    pub maker_address: Address,
+    pub earnings:      f64,
+    pub asset_rate:    f64,
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use serde_json::json;
+    use super::*;
+
+    #[test]
+    fn test_deserialize_earnings() {
+        let earnings: UserEarnings = serde_json::from_value(json!({
+            "data": [{
+                "date":          "2024-06-01",
+                "condition_id":  "0x0101010101010101010101010101010101010101010101010101010101010101",
+                "asset_address": "0x2791bca1f2de4661ed88a30c99a7a9449aa84174",
+                "maker_address": "0x0000000000000000000000000000000000000001",
+                "earnings":      1.25,
+                "asset_rate":    1.0,
+            }],
+            "next_cursor": "LTE=",
+        })).unwrap();
+        assert_eq!(earnings.data[0].date, NaiveDate::from_ymd_opt(2024, 6, 1).unwrap());
+        assert_eq!(earnings.data[0].maker_address, Address::with_last_byte(1));
+
+        let percentages: HashMap<ConditionId, f64> = serde_json::from_value(json!({
+            "0x0101010101010101010101010101010101010101010101010101010101010101": 12.5,
+        })).unwrap();
+        assert_eq!(percentages.values().next(), Some(&12.5));
+    }
+}
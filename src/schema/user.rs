@@ -0,0 +1,29 @@
+use std::collections::HashMap;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum AssetType {
+    // USDC.
+    Collateral,
+    // Outcome token, requires a token id.
+    Conditional,
+}
+
+impl AssetType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AssetType::Collateral  => "COLLATERAL",
+            AssetType::Conditional => "CONDITIONAL",
+        }
+    }
+}
+
+// Amounts are in base units, 6 decimals for both USDC and outcome tokens.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BalanceAllowance {
+    pub balance:    String,
+    // Allowance per exchange contract address.
+    #[serde(default)]
+    pub allowances: HashMap<String, String>,
+}
@@ -0,0 +1,187 @@
+// Serde helpers for the API wire formats of typed fields.
+
+// Serializes a type as its `Display` string and deserializes it with `FromStr`.
+macro_rules! serde_via_str {
+    ($ty:ty) => {
+        impl serde::Serialize for $ty {
+            fn serialize<S: serde::Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+                serializer.collect_str(self)
+            }
+        }
+
+        impl<'de> serde::Deserialize<'de> for $ty {
+            fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+                String::deserialize(deserializer)?.parse().map_err(serde::de::Error::custom)
+            }
+        }
+    };
+}
+
+pub(crate) use serde_via_str;
+
+// Optional ISO 8601 timestamps, the API uses a few formats and empty strings for none.
+// Serialized as RFC 3339.
+pub(crate) mod iso_datetime_opt {
+    use chrono::{DateTime, NaiveDate, SecondsFormat, Utc};
+    use serde::{de::Error, Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(value: &Option<DateTime<Utc>>, serializer: S) -> Result<S::Ok, S::Error> {
+        match value {
+            Some(value) => serializer.serialize_str(&value.to_rfc3339_opts(SecondsFormat::AutoSi, true)),
+            None        => serializer.serialize_none(),
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<DateTime<Utc>>, D::Error> {
+        let Some(s) = Option::<String>::deserialize(deserializer)? else {
+            return Ok(None);
+        };
+        if s.is_empty() {
+            return Ok(None);
+        }
+        if let Ok(datetime) = DateTime::parse_from_rfc3339(&s) {
+            return Ok(Some(datetime.to_utc()));
+        }
+        // E.g. game start times, "2024-01-07 21:30:00+00".
+        if let Ok(datetime) = DateTime::parse_from_str(&s, "%Y-%m-%d %H:%M:%S%#z") {
+            return Ok(Some(datetime.to_utc()));
+        }
+        NaiveDate::parse_from_str(&s, "%Y-%m-%d")
+            .map(|date| Some(date.and_hms_opt(0, 0, 0).unwrap().and_utc()))
+            .map_err(|_| D::Error::custom(format!("invalid timestamp: {}", s)))
+    }
+}
+
+// UTC milliseconds timestamp as a string.
+pub(crate) mod millis_str {
+    use chrono::{DateTime, Utc};
+    use serde::{de::Error, Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(value: &DateTime<Utc>, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(&value.timestamp_millis())
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<DateTime<Utc>, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        s.parse::<i64>()
+            .ok()
+            .and_then(DateTime::from_timestamp_millis)
+            .ok_or_else(|| D::Error::custom(format!("invalid millis timestamp: {}", s)))
+    }
+}
+
+// UTC seconds timestamp as a string where "0" means none, as with order expirations.
+pub(crate) mod seconds_str_opt {
+    use chrono::{DateTime, Utc};
+    use serde::{de::Error, Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(value: &Option<DateTime<Utc>>, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(&value.map_or(0, |value| value.timestamp()))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<DateTime<Utc>>, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        match s.parse::<i64>() {
+            Ok(0) => Ok(None),
+            Ok(secs) => DateTime::from_timestamp(secs, 0)
+                .map(Some)
+                .ok_or_else(|| D::Error::custom(format!("invalid seconds timestamp: {}", s))),
+            Err(_) => Err(D::Error::custom(format!("invalid seconds timestamp: {}", s))),
+        }
+    }
+}
+
+// Empty strings are none, for fields the API leaves empty on failure.
+pub(crate) mod empty_as_none {
+    use std::{fmt::Display, str::FromStr};
+    use serde::{de::Error, Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<T: Display, S: Serializer>(value: &Option<T>, serializer: S) -> Result<S::Ok, S::Error> {
+        match value {
+            Some(value) => serializer.collect_str(value),
+            None        => serializer.serialize_str(""),
+        }
+    }
+
+    pub fn deserialize<'de, T, D>(deserializer: D) -> Result<Option<T>, D::Error>
+    where
+        T: FromStr,
+        T::Err: Display,
+        D: Deserializer<'de>,
+    {
+        let s = Option::<String>::deserialize(deserializer)?.unwrap_or_default();
+        if s.is_empty() {
+            return Ok(None);
+        }
+        s.parse().map(Some).map_err(D::Error::custom)
+    }
+}
+
+pub(crate) mod u256_as_dec_str {
+    use alloy::primitives::U256;
+    use serde::{de::Error, Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(value: &U256, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&value.to_string())
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<U256, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        U256::from_str_radix(&s, 10).map_err(D::Error::custom)
+    }
+}
+
+// Order salts are JSON numbers.
+pub(crate) mod u256_as_u128 {
+    use alloy::primitives::U256;
+    use serde::{de, ser, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(value: &U256, serializer: S) -> Result<S::Ok, S::Error> {
+        let value = u128::try_from(*value).map_err(|_| ser::Error::custom(format!("{} does not fit in a u128", value)))?;
+        serializer.serialize_u128(value)
+    }
+
+    // Any integer visitor, as flattened structs are buffered without u128 support.
+    struct Visitor;
+
+    impl<'de> de::Visitor<'de> for Visitor {
+        type Value = U256;
+
+        fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+            f.write_str("an unsigned integer")
+        }
+
+        fn visit_u64<E: de::Error>(self, value: u64) -> Result<U256, E> {
+            Ok(U256::from(value))
+        }
+
+        fn visit_u128<E: de::Error>(self, value: u128) -> Result<U256, E> {
+            Ok(U256::from(value))
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<U256, D::Error> {
+        deserializer.deserialize_any(Visitor)
+    }
+}
+
+// Order side as "BUY" for 0 and "SELL" for 1.
+pub(crate) mod side_as_str {
+    use serde::{de::Error, ser, Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(value: &u8, serializer: S) -> Result<S::Ok, S::Error> {
+        match value {
+            0 => serializer.serialize_str("BUY"),
+            1 => serializer.serialize_str("SELL"),
+            _ => Err(ser::Error::custom("invalid side, must be 0 for buy, 1 for sell"))
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<u8, D::Error> {
+        match String::deserialize(deserializer)?.as_str() {
+            "BUY"  => Ok(0),
+            "SELL" => Ok(1),
+            side   => Err(D::Error::custom(format!("invalid side: {}", side))),
+        }
+    }
+}
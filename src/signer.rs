@@ -0,0 +1,194 @@
+use std::{fmt, path::Path, str::FromStr, sync::Arc};
+use alloy::{
+    consensus::SignableTransaction,
+    network::TxSigner,
+    primitives::{Address, ChainId, B256},
+    signers::{
+        local::{coins_bip39::English, MnemonicBuilder, PrivateKeySigner},
+        Signature, Signer,
+    },
+};
+use crate::{Error, Result};
+
+// Default derivation path for the first account of a mnemonic.
+pub const DEFAULT_DERIVATION_PATH: &str = "m/44'/60'/0'/0/0";
+
+// Any EIP-712 capable signer can be used by the client, local keys and remote services alike.
+pub trait ClobSigner: Signer + Send + Sync + fmt::Debug {}
+
+impl<T: Signer + Send + Sync + fmt::Debug> ClobSigner for T {}
+
+pub fn keystore_signer(path: impl AsRef<Path>, password: &str) -> Result<PrivateKeySigner> {
+    Ok(PrivateKeySigner::decrypt_keystore(path, password)?)
+}
+
+// Derivation path defaults to the first account if none.
+pub fn mnemonic_signer(phrase: &str, derivation_path: Option<&str>) -> Result<PrivateKeySigner> {
+    Ok(
+        MnemonicBuilder::<English>::default()
+            .phrase(phrase)
+            .derivation_path(derivation_path.unwrap_or(DEFAULT_DERIVATION_PATH))?
+            .build()?
+    )
+}
+
+// Signs transactions with a client signer, so any signer can send on-chain transactions.
+#[derive(Debug, Clone)]
+pub(crate) struct TxSignerAdapter(pub Arc<dyn ClobSigner>);
+
+#[async_trait::async_trait]
+impl TxSigner<Signature> for TxSignerAdapter {
+
+    fn address(&self) -> Address {
+        self.0.address()
+    }
+
+    async fn sign_transaction(&self, tx: &mut dyn SignableTransaction<Signature>) -> alloy::signers::Result<Signature> {
+        // EIP-155 replay protection, as done by alloy's local signers.
+        if let Some(chain_id) = self.0.chain_id() {
+            if !tx.set_chain_id_checked(chain_id) {
+                return Err(alloy::signers::Error::TransactionChainIdMismatch {
+                    signer: chain_id,
+                    // Can only fail if the tx has a chain id.
+                    tx:     tx.chain_id().unwrap(),
+                });
+            }
+        }
+        self.0.sign_hash(&tx.signature_hash()).await
+    }
+}
+
+// Signer that delegates signing to a remote HTTP service, the key never touches this process.
+// The service receives a POST with `{"address": "0x..", "hash": "0x.."}` and must respond
+// with `{"signature": "0x.."}`, a 65 byte signature over the raw hash.
+#[derive(Debug, Clone)]
+pub struct RemoteSigner {
+    client:   reqwest::Client,
+    url:      String,
+    address:  Address,
+    chain_id: Option<ChainId>,
+}
+
+impl RemoteSigner {
+    pub fn new(url: &str, address: &str) -> Result<Self> {
+        Ok(Self {
+            client:   reqwest::Client::new(),
+            url:      url.to_string(),
+            address:  Address::from_str(address).map_err(|_| Error::InvalidSignerAddress)?,
+            chain_id: None,
+        })
+    }
+}
+
+#[async_trait::async_trait]
+impl Signer for RemoteSigner {
+
+    async fn sign_hash(&self, hash: &B256) -> alloy::signers::Result<Signature> {
+
+        #[derive(serde::Deserialize)]
+        struct Resp {
+            signature: String,
+        }
+
+        let body = serde_json::json!({
+            "address": self.address,
+            "hash":    hash,
+        });
+        let resp = self.client
+            .post(&self.url)
+            .json(&body)
+            .send()
+            .await
+            .and_then(|resp| resp.error_for_status())
+            .map_err(alloy::signers::Error::other)?
+            .json::<Resp>()
+            .await
+            .map_err(alloy::signers::Error::other)?;
+
+        let signature = Signature::from_str(&resp.signature).map_err(alloy::signers::Error::other)?;
+        // Guard against a misconfigured service signing with a different key.
+        if signature.recover_address_from_prehash(hash)? != self.address {
+            return Err(alloy::signers::Error::other("remote signature does not match signer address"));
+        }
+        Ok(signature)
+    }
+
+    fn address(&self) -> Address {
+        self.address
+    }
+
+    fn chain_id(&self) -> Option<ChainId> {
+        self.chain_id
+    }
+
+    fn set_chain_id(&mut self, chain_id: Option<ChainId>) {
+        self.chain_id = chain_id;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tokio::{io::{AsyncReadExt, AsyncWriteExt}, net::TcpListener};
+    use alloy::{hex::ToHexExt, signers::SignerSync};
+    use super::*;
+
+    // Known private key and mnemonic for testing, the mnemonic derives the key at index 0.
+    const PRIVATE_KEY: &str = "0xac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80";
+    const MNEMONIC: &str = "test test test test test test test test test test test junk";
+
+    // Stand-in signing service, answers a single request.
+    async fn serve_once(listener: TcpListener, key: PrivateKeySigner) {
+        let (mut stream, _) = listener.accept().await.unwrap();
+        let mut buf = vec![0u8; 4096];
+        let mut len = 0;
+        // Read until the json body is complete.
+        while !buf[..len].ends_with(b"}") {
+            len += stream.read(&mut buf[len..]).await.unwrap();
+        }
+        let req = String::from_utf8_lossy(&buf[..len]);
+        let body: serde_json::Value = serde_json::from_str(&req[req.find("\r\n\r\n").unwrap() + 4..]).unwrap();
+        let hash = B256::from_str(body["hash"].as_str().unwrap()).unwrap();
+        let sig = key.sign_hash_sync(&hash).unwrap();
+
+        let resp_body = format!("{{\"signature\":\"{}\"}}", sig.as_bytes().encode_hex_with_prefix());
+        let resp = format!(
+            "HTTP/1.1 200 OK\r\ncontent-type: application/json\r\ncontent-length: {}\r\n\r\n{}",
+            resp_body.len(),
+            resp_body,
+        );
+        stream.write_all(resp.as_bytes()).await.unwrap();
+    }
+
+    #[test]
+    fn test_mnemonic_signer() {
+        let key = PrivateKeySigner::from_str(PRIVATE_KEY).unwrap();
+        assert_eq!(mnemonic_signer(MNEMONIC, None).unwrap().address(), key.address());
+        assert_ne!(mnemonic_signer(MNEMONIC, Some("m/44'/60'/0'/0/1")).unwrap().address(), key.address());
+    }
+
+    #[test]
+    fn test_keystore_signer() {
+        let dir = std::env::temp_dir().join(format!("clob-keystore-{}", rand::random::<u64>()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let key = PrivateKeySigner::from_str(PRIVATE_KEY).unwrap();
+        PrivateKeySigner::encrypt_keystore(&dir, &mut rand::thread_rng(), key.to_bytes(), "password", Some("key")).unwrap();
+
+        assert_eq!(keystore_signer(dir.join("key"), "password").unwrap().address(), key.address());
+        assert!(keystore_signer(dir.join("key"), "wrong").is_err());
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_remote_signer() {
+        let key = PrivateKeySigner::from_str(PRIVATE_KEY).unwrap();
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let server = tokio::spawn(serve_once(listener, key.clone()));
+
+        let remote = RemoteSigner::new(&url, &key.address().to_string()).unwrap();
+        let hash = B256::repeat_byte(0x42);
+        let sig = remote.sign_hash(&hash).await.unwrap();
+        assert_eq!(sig, key.sign_hash_sync(&hash).unwrap());
+        server.await.unwrap();
+    }
+}
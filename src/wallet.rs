@@ -0,0 +1,60 @@
+use alloy::{primitives::{b256, keccak256, Address, B256}, sol_types::SolValue};
+
+// Derivation of the Polymarket funding wallets owned by a signer, both are deployed with CREATE2.
+
+// Init code hash of the Polymarket proxy wallet.
+pub const PROXY_INIT_CODE_HASH: B256 = b256!("d21df8dc65880a8606f09fe0ce3df9b8869287ab0b058be05aa9e8af6330a00b");
+// Init code hash of the Gnosis safe proxy deployed by the Polymarket safe factory.
+pub const SAFE_INIT_CODE_HASH: B256 = b256!("2bce2127ff07fb632d16c8347c4ebf501f4841168bed00d9e6ef715ddb6fcecf");
+
+// Proxy wallet salt is the packed signer address.
+pub fn proxy_wallet_address(proxy_factory: Address, signer: Address) -> Address {
+    proxy_factory.create2(keccak256(signer.abi_encode_packed()), PROXY_INIT_CODE_HASH)
+}
+
+// Safe salt is the abi encoded (padded) signer address.
+pub fn safe_address(safe_factory: Address, signer: Address) -> Address {
+    safe_factory.create2(keccak256(signer.abi_encode()), SAFE_INIT_CODE_HASH)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+    use crate::{client::{ClobClient, SignatureType}, contracts::MATIC_CONTRACTS, Error};
+    use super::*;
+
+    const SIGNER: &str = "0xf39Fd6e51aad88F6F4ce6aB8827279cffFb92266";
+
+    #[test]
+    fn test_wallet_addresses() {
+        let signer = Address::from_str(SIGNER).unwrap();
+        let proxy_factory = MATIC_CONTRACTS.proxy_factory.unwrap();
+        let safe_factory = MATIC_CONTRACTS.safe_factory.unwrap();
+
+        // Wallets of the signer on the Polygon factories.
+        assert_eq!(proxy_wallet_address(proxy_factory, signer), Address::from_str("0x365f0CA36Ae1f641E02fE3B7743673da42A13A70").unwrap());
+        assert_eq!(safe_address(safe_factory, signer), Address::from_str("0xd93B25cb943D14d0d34FBaF01Fc93a0f8b5F6E47").unwrap());
+        // The salts differ, packed and padded, so the same factory gives different addresses.
+        assert_ne!(proxy_wallet_address(proxy_factory, signer), safe_address(proxy_factory, signer));
+    }
+
+    #[test]
+    fn test_client_proxy_validation() {
+        // Private key of SIGNER.
+        let client = ClobClient::new("http://localhost")
+            .with_signer("0xac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80", 137)
+            .unwrap();
+        let safe = client.derive_proxy_address(SignatureType::PolyGnosisSafe).unwrap();
+        let proxy = client.derive_proxy_address(SignatureType::PolyProxy).unwrap();
+
+        assert!(client.clone().with_proxy(&safe.to_string(), SignatureType::PolyGnosisSafe).is_ok());
+        assert!(matches!(
+            client.clone().with_proxy(&proxy.to_string(), SignatureType::PolyGnosisSafe),
+            Err(Error::ProxyAddressMismatch { .. })
+        ));
+        assert!(client.with_proxy_unchecked(&proxy.to_string(), SignatureType::PolyGnosisSafe).is_ok());
+
+        // Nothing to validate against without a signer.
+        assert!(ClobClient::new("http://localhost").with_proxy(&proxy.to_string(), SignatureType::PolyProxy).is_ok());
+    }
+}